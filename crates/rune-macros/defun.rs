@@ -117,14 +117,16 @@ fn get_arg_conversion(args: &[ArgType]) -> Vec<TokenStream> {
             // Rt<Gc<..>>
             ArgType::Rt(gc) => match gc {
                 Gc::Obj => quote! {&args[#idx]},
-                Gc::Other => quote! {args[#idx].try_as()?},
+                Gc::Other => with_arg_index(quote! {args[#idx].try_as()}, idx),
             },
             // Gc<..>
             ArgType::Gc(gc) => {
                 let bind = quote! {args[#idx].bind(cx)};
                 match gc {
                     Gc::Obj => bind,
-                    Gc::Other => quote! { std::convert::TryFrom::try_from(#bind)? },
+                    Gc::Other => {
+                        with_arg_index(quote! { std::convert::TryFrom::try_from(#bind) }, idx)
+                    }
                 }
             }
             // &[Gc<..>]
@@ -148,9 +150,10 @@ fn get_arg_conversion(args: &[ArgType]) -> Vec<TokenStream> {
             }
             // Option<Rt<Gc<..>>>
             ArgType::OptionRt => {
+                let convert = with_arg_index(quote! {crate::core::gc::Rt::try_as_option(x)}, idx);
                 quote! {
                     match args.get(#idx) {
-                        Some(x) => crate::core::gc::Rt::try_as_option(x)?,
+                        Some(x) => #convert,
                         None => None,
                     }
                 }
@@ -158,25 +161,33 @@ fn get_arg_conversion(args: &[ArgType]) -> Vec<TokenStream> {
             // Option<T>
             ArgType::Option => {
                 let bind = quote! {x.bind(cx)};
+                let convert =
+                    with_arg_index(quote! {crate::core::object::Gc::try_from_option(#bind)}, idx);
                 quote! {
                     match args.get(#idx) {
-                        Some(x) => crate::core::object::Gc::try_from_option(#bind)?,
+                        Some(x) => #convert,
                         None => None,
                     }
                 }
             }
             ArgType::Other => {
                 if is_mut {
-                    quote! { std::convert::TryFrom::try_from(&args[#idx])? }
+                    with_arg_index(quote! { std::convert::TryFrom::try_from(&args[#idx]) }, idx)
                 } else {
                     let bind = quote! {args[#idx].bind(cx)};
-                    quote! { std::convert::TryFrom::try_from(#bind)? }
+                    with_arg_index(quote! { std::convert::TryFrom::try_from(#bind) }, idx)
                 }
             }
         })
         .collect()
 }
 
+/// Propagate a failed argument conversion, tagging type errors with the
+/// position of the argument.
+fn with_arg_index(convert: TokenStream, idx: usize) -> TokenStream {
+    quote! { #convert.map_err(|e| crate::core::error::arg_type_error(e, #idx))? }
+}
+
 fn parse_call_signature(args: &[ArgType], spec_required: Option<u16>) -> (u16, u16, bool) {
     let required = {
         let actual_required = args.iter().filter(|x| x.is_required_arg()).count();
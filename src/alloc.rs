@@ -2,7 +2,8 @@
 use crate::core::cons::Cons;
 use crate::core::gc::Context;
 use crate::core::object::{
-    ByteFn, ByteString, FnArgs, Gc, IntoObject, LispVec, Object, RecordBuilder, Symbol, NIL,
+    ByteFn, ByteString, FnArgs, Gc, IntoObject, LispVec, MarkerInner, Object, RecordBuilder,
+    Symbol, NIL,
 };
 use anyhow::{ensure, Result};
 use rune_macros::defun;
@@ -16,8 +17,16 @@ pub(crate) fn list<'ob>(objects: &[Object<'ob>], cx: &'ob Context) -> Object<'ob
     head
 }
 
+/// Multiple values are represented as a list of the values.
+#[defun]
+fn cl_values<'ob>(values: &[Object<'ob>], cx: &'ob Context) -> Object<'ob> {
+    list(values, cx)
+}
+
 /// Convert a function to closure by replacing the first N elements with their
 /// closure values.
+/// The prototype is copied rather than modified, so any number of variables
+/// can be captured as long as the constant vector has room for them.
 #[defun]
 pub(crate) fn make_closure<'ob>(
     prototype: &ByteFn,
@@ -44,6 +53,12 @@ pub(crate) fn make_closure<'ob>(
     }
 }
 
+/// Create a byte-code function. The constant vector is used as is: constants
+/// are never merged, even if they are `equal`. Two quoted literals in a
+/// function stay distinct objects unless the byte compiler already shared
+/// them, so mutating one can't change the other. The code is checked with
+/// [`crate::bytecode::verify`] so malformed code is rejected here instead of
+/// when it runs.
 #[defun]
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn make_byte_code<'ob>(
@@ -56,12 +71,20 @@ pub(crate) fn make_byte_code<'ob>(
     _elements: &[Object],
     cx: &'ob Context,
 ) -> Result<&'ob ByteFn> {
+    let args = FnArgs::from_arg_spec(arglist)?;
+    crate::bytecode::verify(byte_code, &constants.to_vec(), args)?;
     unsafe {
-        let bytefn = ByteFn::make(byte_code, constants, FnArgs::from_arg_spec(arglist)?, depth);
+        let bytefn = ByteFn::make(byte_code, constants, args, depth);
         Ok(bytefn.into_obj(cx).untag())
     }
 }
 
+/// Return a new marker that does not point anywhere.
+#[defun]
+fn make_marker<'ob>(cx: &'ob Context) -> Object<'ob> {
+    cx.add(MarkerInner::new(None))
+}
+
 #[defun]
 fn make_vector(length: usize, init: Object) -> Vec<Object> {
     vec![init; length]
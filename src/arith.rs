@@ -1,5 +1,5 @@
 //! Arithmetic operators.
-use crate::core::object::{Gc, IntoObject, Number, NumberType, ObjectType};
+use crate::core::object::{Gc, IntoObject, Number, NumberType, Object, ObjectType};
 use float_cmp::ApproxEq;
 use rune_macros::defun;
 use std::cmp::PartialEq;
@@ -214,6 +214,31 @@ pub(crate) fn greater_than_or_eq(number: Number, numbers: &[Number]) -> bool {
     cmp(number, numbers, NumberValue::ge)
 }
 
+#[defun]
+pub(crate) fn zerop(number: Number) -> bool {
+    number == 0
+}
+
+#[defun]
+pub(crate) fn natnump(object: Object) -> bool {
+    matches!(object.untag(), ObjectType::Int(x) if x >= 0)
+}
+
+#[defun]
+pub(crate) fn wholenump(object: Object) -> bool {
+    natnump(object)
+}
+
+#[defun]
+pub(crate) fn cl_plusp(number: Number) -> bool {
+    number.val() > NumberValue::Int(0)
+}
+
+#[defun]
+pub(crate) fn cl_minusp(number: Number) -> bool {
+    number.val() < NumberValue::Int(0)
+}
+
 #[defun]
 pub(crate) fn logior(ints_or_markers: &[Gc<i64>]) -> i64 {
     ints_or_markers.iter().fold(0, |acc, x| acc | x.untag())
@@ -235,10 +260,15 @@ pub(crate) fn remainder(x: i64, y: i64) -> i64 {
     x % y
 }
 
+fn is_nan(x: NumberValue) -> bool {
+    matches!(x, NumberValue::Float(f) if f.is_nan())
+}
+
 #[allow(clippy::trivially_copy_pass_by_ref)]
 fn max_val(x: NumberValue, y: &Number) -> NumberValue {
     let y = y.val();
-    if x > y {
+    // NaN is contagious, like in Emacs
+    if is_nan(x) || x > y {
         x
     } else {
         y
@@ -248,7 +278,7 @@ fn max_val(x: NumberValue, y: &Number) -> NumberValue {
 #[allow(clippy::trivially_copy_pass_by_ref)]
 fn min_val(x: NumberValue, y: &Number) -> NumberValue {
     let y = y.val();
-    if x < y {
+    if is_nan(x) || x < y {
         x
     } else {
         y
@@ -342,6 +372,65 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_max_min_mixed() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        assert_eq!(max(1.into(), &[cx.add_as(2.5), 2.into()]), NumberValue::Float(2.5));
+        assert_eq!(max(3.into(), &[cx.add_as(2.5)]), NumberValue::Int(3));
+        assert_eq!(min(3.into(), &[cx.add_as(2.5)]), NumberValue::Float(2.5));
+        assert_eq!(min(cx.add_as(2.5), &[1.into(), 4.into()]), NumberValue::Int(1));
+        assert_eq!(max((-1).into(), &[]), NumberValue::Int(-1));
+        assert!(is_nan(max(1.into(), &[cx.add_as(f64::NAN), 2.into()])));
+        assert!(is_nan(min(cx.add_as(f64::NAN), &[1.into()])));
+    }
+
+    #[test]
+    fn test_max_min_lisp() {
+        use crate::interpreter::assert_lisp;
+        assert_lisp("(max 1 2.5 2)", "2.5");
+        assert_lisp("(min 1 2.5 -3)", "-3");
+        assert_lisp("(condition-case nil (max) (error 'err))", "err");
+        assert_lisp("(condition-case nil (min 1 'a) (error 'err))", "err");
+    }
+
+    #[test]
+    fn test_predicates() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        assert!(zerop(0.into()));
+        assert!(zerop(cx.add_as(0.0)));
+        assert!(zerop(cx.add_as(-0.0)));
+        assert!(!zerop(1.into()));
+        assert!(!zerop(cx.add_as(0.5)));
+
+        assert!(natnump(0.into()));
+        assert!(natnump(5.into()));
+        assert!(!natnump((-1).into()));
+        assert!(!natnump(cx.add(1.0)));
+        assert!(!natnump(cx.add("1")));
+        assert!(wholenump(3.into()));
+        assert!(!wholenump((-3).into()));
+
+        assert!(cl_plusp(1.into()));
+        assert!(cl_plusp(cx.add_as(0.1)));
+        assert!(!cl_plusp(0.into()));
+        assert!(!cl_plusp((-1).into()));
+        assert!(cl_minusp((-1).into()));
+        assert!(cl_minusp(cx.add_as(-0.1)));
+        assert!(!cl_minusp(0.into()));
+        assert!(!cl_minusp(cx.add_as(1.5)));
+    }
+
+    #[test]
+    fn test_type_errors() {
+        use crate::interpreter::assert_lisp;
+        assert_lisp("(condition-case nil (zerop 'a) (error 'err))", "err");
+        assert_lisp("(condition-case nil (cl-plusp \"1\") (error 'err))", "err");
+        assert_lisp("(condition-case nil (cl-minusp nil) (error 'err))", "err");
+        assert_lisp("(natnump 'a)", "nil");
+    }
+
     #[test]
     fn test_other() {
         let roots = &RootSet::default();
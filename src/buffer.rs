@@ -268,6 +268,23 @@ mod test {
         assert!(new_name.starts_with(" gen_buffer_test-"));
     }
 
+    #[test]
+    fn test_gen_new_buffer_name_distinct() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+
+        let base = "gen_buffer_distinct";
+        let mut names = Vec::new();
+        for _ in 0..4 {
+            let name = generate_new_buffer_name(base, None);
+            assert!(!names.contains(&name));
+            get_buffer_create(cx.add(name.as_str()), Some(NIL), cx).unwrap();
+            names.push(name);
+        }
+        assert_eq!(names[0], base);
+        assert_eq!(names[3], "gen_buffer_distinct<4>");
+    }
+
     #[test]
     fn test_create_buffer() {
         let roots = &RootSet::default();
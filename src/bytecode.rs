@@ -4,92 +4,104 @@ use crate::core::cons::Cons;
 use crate::core::env::{sym, CallFrame, Env};
 use crate::core::gc::{Context, IntoRoot, Rt, Rto, Slot};
 use crate::core::object::{
-    ByteFn, ByteString, Function, FunctionType, Gc, LispVec, Object, ObjectType, Symbol,
+    ByteFn, ByteString, FnArgs, Function, FunctionType, Gc, LispVec, Object, ObjectType, Symbol,
     WithLifetime, NIL,
 };
 use crate::eval::{ErrorType, EvalError, EvalResult};
-use anyhow::{bail, Result};
+use anyhow::{bail, ensure, Result};
 use rune_core::macros::{bail_err, rebind, root};
 use rune_macros::{defun, Trace};
 use sptr::Strict;
 
 mod opcode;
 
-/// An program counter. This is implemented as a bound checked range pointer.
-// TODO: If the GC moves the bytecode, this will be invalid. We need to fix this
-#[derive(Clone, Debug)]
+/// An program counter. This is implemented as an offset into the bytecode of
+/// the current function. The code is passed in on every access instead of
+/// being stored, so that it is always read through the rooted function and
+/// stays valid if the GC moves it. Every access is bounds checked, so
+/// malformed bytecode results in an error instead of reading out of bounds.
+#[derive(Clone, Debug, Default)]
 struct ProgramCounter {
-    /// Valid range for this instruction pointer.
-    range: std::ops::Range<*const u8>,
-    /// Points to the next instruction.
-    pc: *const u8,
+    /// Offset of the next instruction.
+    pc: usize,
 }
 
 impl ProgramCounter {
-    fn new(vec: &[u8]) -> Self {
-        ProgramCounter { range: vec.as_ptr_range(), pc: vec.as_ptr() }
+    fn with_offset(offset: usize) -> Self {
+        ProgramCounter { pc: offset }
     }
 
-    fn with_offset(vec: &[u8], offset: usize) -> Self {
-        ProgramCounter { range: vec.as_ptr_range(), pc: vec.as_ptr().map_addr(|a| a + offset) }
+    fn as_offset(&self) -> usize {
+        self.pc
     }
 
-    fn as_offset(&self) -> usize {
-        self.pc.addr() - self.range.start.addr()
+    fn goto(&mut self, codes: &[u8], offset: u16) -> Result<()> {
+        let offset = usize::from(offset);
+        ensure!(
+            offset < codes.len(),
+            "Invalid Bytecode: jump to {offset} is outside of code of length {}",
+            codes.len()
+        );
+        self.pc = offset;
+        Ok(())
     }
 
-    fn goto(&mut self, offset: u16) {
-        unsafe {
-            self.pc = self.range.start.add(offset as usize);
-            debug_assert!(self.range.contains(&self.pc));
-        }
+    /// Take the next `N` bytes in the stream
+    fn take<const N: usize>(&mut self, codes: &[u8]) -> Result<[u8; N]> {
+        let end = self.pc + N;
+        let Some(bytes) = codes.get(self.pc..end) else {
+            bail!("Invalid Bytecode: code ended at {} without returning", codes.len())
+        };
+        self.pc = end;
+        Ok(bytes.try_into().unwrap())
     }
 
     /// Take the next byte in the stream
-    fn next(&mut self) -> u8 {
-        unsafe {
-            debug_assert!(self.range.contains(&self.pc));
-            let value = *self.pc;
-            self.pc = self.pc.add(1);
-            value
-        }
+    fn next(&mut self, codes: &[u8]) -> Result<u8> {
+        let [value] = self.take(codes)?;
+        Ok(value)
     }
 
-    fn arg1(&mut self) -> u16 {
-        unsafe {
-            debug_assert!(self.range.contains(&self.pc));
-            let value = *self.pc;
-            self.pc = self.pc.add(1);
-            if cfg!(feature = "debug_bytecode") && crate::debug::debug_enabled() {
-                println!("  arg: {value}");
-            }
-            value.into()
+    fn arg1(&mut self, codes: &[u8]) -> Result<u16> {
+        let [value] = self.take(codes)?;
+        if cfg!(feature = "debug_bytecode") && crate::debug::debug_enabled() {
+            println!("  arg: {value}");
         }
+        Ok(value.into())
     }
 
-    fn arg2(&mut self) -> u16 {
-        unsafe {
-            debug_assert!(self.range.contains(&self.pc.add(1)));
-            let value = u16::from_le(self.pc.cast::<u16>().read_unaligned());
-            self.pc = self.pc.add(2);
-            if cfg!(feature = "debug_bytecode") && crate::debug::debug_enabled() {
-                println!("  arg: {value}");
-            }
-            value
+    fn arg2(&mut self, codes: &[u8]) -> Result<u16> {
+        let value = u16::from_le_bytes(self.take(codes)?);
+        if cfg!(feature = "debug_bytecode") && crate::debug::debug_enabled() {
+            println!("  arg: {value}");
         }
+        Ok(value)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HandlerType {
+    ConditionCase,
+    Catch,
+}
+
 #[derive(Debug, Trace)]
-/// A handler for a condition-case. These are stored in a vector in the VM and
-/// added/removed via bytecodes.
+/// A handler for a condition-case or catch. These are stored in a vector in
+/// the VM and added/removed via bytecodes.
 struct Handler<'ob> {
+    #[no_trace]
+    kind: HandlerType,
     #[no_trace]
     jump_code: u16,
     #[no_trace]
     stack_size: usize,
     #[no_trace]
     stack_frame: usize,
+    #[no_trace]
+    binding_depth: usize,
+    #[no_trace]
+    cleanup_count: usize,
+    /// The conditions for a condition-case, or the tag for a catch
     condition: Slot<Object<'ob>>,
 }
 
@@ -119,6 +131,15 @@ struct VM<'brw, 'env, 'rt> {
     func: Slot<&'rt ByteFn>,
     /// All currently active condition-case handlers
     handlers: Vec<Handler<'rt>>,
+    /// Cleanups registered by unwind-protect. They are run when the binding
+    /// stack is unwound past the depth it had when they were pushed.
+    cleanups: Vec<Slot<Object<'rt>>>,
+    /// The binding stack depth for each entry in `cleanups`
+    #[no_trace]
+    cleanup_depths: Vec<usize>,
+    /// Depth of the binding stack when the VM was entered
+    #[no_trace]
+    binding_depth: usize,
     /// The runtime environment
     #[no_trace]
     env: &'brw mut Rt<Env<'env>>,
@@ -132,7 +153,7 @@ impl<'brw, 'env> IntoRoot<VM<'brw, 'env, 'static>> for VM<'brw, 'env, '_> {
 
 impl<'ob> RootedVM<'_, '_, '_> {
     fn varref(&mut self, idx: u16, cx: &'ob Context) -> Result<()> {
-        let symbol = self.get_const(idx as usize, cx);
+        let symbol = self.get_const(idx as usize, cx)?;
         if let ObjectType::Symbol(sym) = symbol.untag() {
             let Some(var) = self.env.vars.get(sym) else { bail!("Void Variable: {sym}") };
             let var = var.bind(cx);
@@ -144,33 +165,102 @@ impl<'ob> RootedVM<'_, '_, '_> {
     }
 
     fn varset(&mut self, idx: usize, cx: &Context) -> Result<()> {
-        let obj = self.get_const(idx, cx);
+        let obj = self.get_const(idx, cx)?;
         let symbol: Symbol = obj.try_into()?;
         let value = self.env.stack.pop(cx);
         crate::data::set(symbol, value, self.env)?;
         Ok(())
     }
 
-    fn varbind(&mut self, idx: u16, cx: &'ob Context) {
+    fn varbind(&mut self, idx: u16, cx: &'ob Context) -> Result<()> {
         let value = self.env.stack.pop(cx);
-        let symbol = self.get_const(idx as usize, cx);
+        let symbol = self.get_const(idx as usize, cx)?;
         let ObjectType::Symbol(sym) = symbol.untag() else {
             unreachable!("Varbind was not a symbol: {:?}", symbol)
         };
         self.env.varbind(sym, value, cx);
+        Ok(())
+    }
+
+    /// Remove the last `count` entries from the binding stack. Cleanups from
+    /// unwind-protect count as entries and are run when they are reached.
+    fn unbind(&mut self, count: u16, cx: &'ob mut Context) -> Result<(), EvalError> {
+        for _ in 0..count {
+            let depth = self.env.binding_depth();
+            if self.cleanup_depths.last() == Some(&depth) {
+                self.cleanup_depths.pop();
+                let cleanup = self.cleanups.bind_mut(cx).pop().unwrap();
+                let cleanup = cx.bind(*cleanup);
+                root!(cleanup, cx);
+                self.run_cleanup(cleanup, cx)?;
+            } else {
+                self.env.unbind(1, cx);
+            }
+        }
+        Ok(())
+    }
+
+    /// Unbind until the binding stack is at `depth` and only `cleanups`
+    /// unwind-protect handlers remain.
+    fn unbind_to(
+        &mut self,
+        depth: usize,
+        cleanups: usize,
+        cx: &'ob mut Context,
+    ) -> Result<(), EvalError> {
+        while self.cleanups.len() > cleanups || self.env.binding_depth() > depth {
+            self.unbind(1, cx)?;
+        }
+        Ok(())
     }
 
-    fn unbind(&mut self, idx: u16, cx: &'ob Context) {
-        self.env.unbind(idx, cx);
+    /// Run an unwind-protect cleanup. Lexical code passes a function to call,
+    /// while older dynamic code passes a list of forms to evaluate.
+    fn run_cleanup(
+        &mut self,
+        cleanup: &Rto<Object>,
+        cx: &'ob mut Context,
+    ) -> Result<(), EvalError> {
+        let cleanup = cleanup.bind(cx);
+        if crate::data::functionp(cleanup, cx) {
+            let func: Function = cleanup.try_into()?;
+            root!(func, cx);
+            func.call(&mut CallFrame::new(self.env), None, cx)?;
+        } else {
+            let form: Object = Cons::new(sym::PROGN, cleanup, cx).into();
+            root!(form, cx);
+            crate::interpreter::eval_toplevel(form, false, self.env, cx)?;
+        }
+        Ok(())
     }
 
-    fn get_const(&self, i: usize, cx: &'ob Context) -> Object<'ob> {
-        *self.func.bind(cx).consts().get(i).expect("constant had invalid index")
+    fn get_const(&self, i: usize, cx: &'ob Context) -> Result<Object<'ob>> {
+        let consts = self.func.bind(cx).consts();
+        match consts.get(i) {
+            Some(cnst) => Ok(*cnst),
+            None => bail!("Invalid Bytecode: constant {i} is out of range of {}", consts.len()),
+        }
     }
 
     fn set_current_frame(&mut self, f: &ByteFn, offset: usize) {
         self.func.set(f);
-        self.pc = ProgramCounter::with_offset(f.codes(), offset);
+        self.pc = ProgramCounter::with_offset(offset);
+    }
+
+    fn next(&mut self, cx: &Context) -> Result<u8> {
+        self.pc.next(self.func.bind(cx).codes())
+    }
+
+    fn arg1(&mut self, cx: &Context) -> Result<u16> {
+        self.pc.arg1(self.func.bind(cx).codes())
+    }
+
+    fn arg2(&mut self, cx: &Context) -> Result<u16> {
+        self.pc.arg2(self.func.bind(cx).codes())
+    }
+
+    fn goto(&mut self, offset: u16, cx: &Context) -> Result<()> {
+        self.pc.goto(self.func.bind(cx).codes(), offset)
     }
 
     fn unwind(&mut self, idx: usize, cx: &'ob Context) {
@@ -254,45 +344,57 @@ impl<'ob> RootedVM<'_, '_, '_> {
 
     fn run(&mut self, cx: &'ob mut Context) -> EvalResult<'ob> {
         'main: loop {
-            let err = match self.execute_bytecode(cx) {
+            let mut err = match self.execute_bytecode(cx) {
                 Ok(x) => return Ok(rebind!(x, cx)),
                 Err(e) => e,
             };
 
-            // we will fix this once we can handle different error types
-            #[allow(clippy::never_loop)]
-            while let Some(handler) = self.handlers.bind_mut(cx).pop() {
-                match handler.condition.untag() {
-                    ObjectType::Symbol(sym::ERROR) => {}
-                    ObjectType::Cons(conditions) => {
-                        for condition in conditions {
-                            let condition = condition?;
-                            // TODO: Handle different error symbols
-                            if condition != sym::DEBUG && condition != sym::ERROR {
-                                bail_err!("non-error conditions {condition} not yet supported")
+            loop {
+                // Run the cleanups above the next handler first. An error in a
+                // cleanup replaces the current one.
+                let (depth, cleanups) = match self.handlers.last() {
+                    Some(handler) => (handler.binding_depth, handler.cleanup_count),
+                    None => (self.binding_depth, 0),
+                };
+                if let Err(e) = self.unbind_to(depth, cleanups, cx) {
+                    err = e;
+                    continue;
+                }
+                let Some(handler) = self.handlers.bind_mut(cx).pop() else { return Err(err) };
+                let value: Object = match handler.kind {
+                    HandlerType::Catch => {
+                        self.env.catch_stack.pop();
+                        let ErrorType::Throw(id) = err.error else { continue };
+                        match self.env.get_exception(id) {
+                            Some((tag, data)) if tag.bind(cx).ptr_eq(*handler.condition) => {
+                                data.bind(cx)
                             }
+                            _ => continue,
+                        }
+                    }
+                    HandlerType::ConditionCase => {
+                        if !crate::eval::handler_matches(*handler.condition, &err, self.env, cx)? {
+                            continue;
+                        }
+                        if let EvalError { error: ErrorType::Signal(id), .. } = err {
+                            let Some((sym, data)) = self.env.get_exception(id) else {
+                                unreachable!("Exception not found")
+                            };
+                            Cons::new(sym, data, cx).into()
+                        } else {
+                            // TODO: Need to remove the anyhow branch once
+                            // full errors are implemented
+                            Cons::new(sym::ERROR, format!("{err}"), cx).into()
                         }
                     }
-                    x => bail_err!("Invalid condition handler: {x}"),
-                }
-
-                let error = if let EvalError { error: ErrorType::Signal(id), .. } = err {
-                    let Some((sym, data)) = self.env.get_exception(id) else {
-                        unreachable!("Exception not found")
-                    };
-                    Cons::new(sym, data, cx)
-                } else {
-                    // TODO: Need to remove the anyhow branch once
-                    // full errors are implemented
-                    Cons::new(sym::ERROR, format!("{err}"), cx)
                 };
+                // Drop any temporaries the interrupted code left on the stack
                 self.unwind(handler.stack_frame, cx);
                 self.env.stack.truncate(handler.stack_size);
-                self.env.stack.push(Object::from(error));
-                self.pc.goto(handler.jump_code);
+                self.env.stack.push(value);
+                self.goto(handler.jump_code, cx)?;
                 continue 'main;
             }
-            return Err(err);
         }
     }
 
@@ -302,9 +404,9 @@ impl<'ob> RootedVM<'_, '_, '_> {
         use crate::{alloc, arith, data, fns};
         use opcode::OpCode as op;
         loop {
-            let op = match self.pc.next().try_into() {
+            let op = match self.next(cx)?.try_into() {
                 Ok(x) => x,
-                Err(e) => panic!("Invalid Bytecode: {e}"),
+                Err(e) => bail_err!("Invalid Bytecode: {e}"),
             };
 
             if Self::debug_enabled() {
@@ -313,7 +415,7 @@ impl<'ob> RootedVM<'_, '_, '_> {
                     println!("    {idx}: {x},");
                 }
                 println!("]");
-                let byte_offset = self.pc.pc as i64 - self.pc.range.start as i64 - 1;
+                let byte_offset = self.pc.as_offset() - 1;
                 println!("op :{byte_offset}: {op:?}");
             }
             match op {
@@ -324,19 +426,19 @@ impl<'ob> RootedVM<'_, '_, '_> {
                 op::StackRef4 => self.env.stack.push_ref(4, cx),
                 op::StackRef5 => self.env.stack.push_ref(5, cx),
                 op::StackRefN => {
-                    let idx = self.pc.arg1();
+                    let idx = self.arg1(cx)?;
                     self.env.stack.push_ref(idx, cx);
                 }
                 op::StackRefN2 => {
-                    let idx = self.pc.arg2();
+                    let idx = self.arg2(cx)?;
                     self.env.stack.push_ref(idx, cx);
                 }
                 op::StackSetN => {
-                    let idx = self.pc.arg1();
+                    let idx = self.arg1(cx)?;
                     self.env.stack.set_ref(idx);
                 }
                 op::StackSetN2 => {
-                    let idx = self.pc.arg2();
+                    let idx = self.arg2(cx)?;
                     self.env.stack.set_ref(idx);
                 }
                 op::VarRef0 => self.varref(0, cx)?,
@@ -346,11 +448,11 @@ impl<'ob> RootedVM<'_, '_, '_> {
                 op::VarRef4 => self.varref(4, cx)?,
                 op::VarRef5 => self.varref(5, cx)?,
                 op::VarRefN => {
-                    let idx = self.pc.arg1();
+                    let idx = self.arg1(cx)?;
                     self.varref(idx, cx)?;
                 }
                 op::VarRefN2 => {
-                    let idx = self.pc.arg2();
+                    let idx = self.arg2(cx)?;
                     self.varref(idx, cx)?;
                 }
                 op::VarSet0 => self.varset(0, cx)?,
@@ -360,26 +462,26 @@ impl<'ob> RootedVM<'_, '_, '_> {
                 op::VarSet4 => self.varset(4, cx)?,
                 op::VarSet5 => self.varset(5, cx)?,
                 op::VarSetN => {
-                    let idx = self.pc.arg1();
+                    let idx = self.arg1(cx)?;
                     self.varset(idx.into(), cx)?;
                 }
                 op::VarSetN2 => {
-                    let idx = self.pc.arg2();
+                    let idx = self.arg2(cx)?;
                     self.varset(idx.into(), cx)?;
                 }
-                op::VarBind0 => self.varbind(0, cx),
-                op::VarBind1 => self.varbind(1, cx),
-                op::VarBind2 => self.varbind(2, cx),
-                op::VarBind3 => self.varbind(3, cx),
-                op::VarBind4 => self.varbind(4, cx),
-                op::VarBind5 => self.varbind(5, cx),
+                op::VarBind0 => self.varbind(0, cx)?,
+                op::VarBind1 => self.varbind(1, cx)?,
+                op::VarBind2 => self.varbind(2, cx)?,
+                op::VarBind3 => self.varbind(3, cx)?,
+                op::VarBind4 => self.varbind(4, cx)?,
+                op::VarBind5 => self.varbind(5, cx)?,
                 op::VarBindN => {
-                    let idx = self.pc.arg1();
-                    self.varbind(idx, cx);
+                    let idx = self.arg1(cx)?;
+                    self.varbind(idx, cx)?;
                 }
                 op::VarBindN2 => {
-                    let idx = self.pc.arg2();
-                    self.varbind(idx, cx);
+                    let idx = self.arg2(cx)?;
+                    self.varbind(idx, cx)?;
                 }
                 op::Call0 => self.call(0, cx)?,
                 op::Call1 => self.call(1, cx)?,
@@ -388,42 +490,62 @@ impl<'ob> RootedVM<'_, '_, '_> {
                 op::Call4 => self.call(4, cx)?,
                 op::Call5 => self.call(5, cx)?,
                 op::CallN => {
-                    let idx = self.pc.arg1();
+                    let idx = self.arg1(cx)?;
                     self.call(idx, cx)?;
                 }
                 op::CallN2 => {
-                    let idx = self.pc.arg2();
+                    let idx = self.arg2(cx)?;
                     self.call(idx, cx)?;
                 }
-                op::Unbind0 => self.unbind(0, cx),
-                op::Unbind1 => self.unbind(1, cx),
-                op::Unbind2 => self.unbind(2, cx),
-                op::Unbind3 => self.unbind(3, cx),
-                op::Unbind4 => self.unbind(4, cx),
-                op::Unbind5 => self.unbind(5, cx),
+                op::Unbind0 => self.unbind(0, cx)?,
+                op::Unbind1 => self.unbind(1, cx)?,
+                op::Unbind2 => self.unbind(2, cx)?,
+                op::Unbind3 => self.unbind(3, cx)?,
+                op::Unbind4 => self.unbind(4, cx)?,
+                op::Unbind5 => self.unbind(5, cx)?,
                 op::UnbindN => {
-                    let idx = self.pc.arg1();
-                    self.unbind(idx, cx);
+                    let idx = self.arg1(cx)?;
+                    self.unbind(idx, cx)?;
                 }
                 op::UnbindN2 => {
-                    let idx = self.pc.arg2();
-                    self.unbind(idx, cx);
+                    let idx = self.arg2(cx)?;
+                    self.unbind(idx, cx)?;
                 }
                 op::PopHandler => {
-                    self.handlers.pop();
+                    if let Some(Handler { kind: HandlerType::Catch, .. }) =
+                        self.handlers.bind_mut(cx).pop()
+                    {
+                        self.env.catch_stack.pop();
+                    }
                 }
                 op::PushCondtionCase => {
                     // pop before getting stack size
                     let condition = self.env.stack.pop(cx);
                     let handler = Handler {
-                        jump_code: self.pc.arg2(),
+                        kind: HandlerType::ConditionCase,
+                        jump_code: self.arg2(cx)?,
                         stack_size: self.env.stack.len(),
                         stack_frame: self.env.stack.current_frame(),
+                        binding_depth: self.env.binding_depth(),
+                        cleanup_count: self.cleanups.len(),
                         condition: Slot::new(condition),
                     };
                     self.handlers.push(handler);
                 }
-                op::PushCatch => todo!("PushCatch bytecode"),
+                op::PushCatch => {
+                    let tag = self.env.stack.pop(cx);
+                    self.env.catch_stack.push(tag);
+                    let handler = Handler {
+                        kind: HandlerType::Catch,
+                        jump_code: self.arg2(cx)?,
+                        stack_size: self.env.stack.len(),
+                        stack_frame: self.env.stack.current_frame(),
+                        binding_depth: self.env.binding_depth(),
+                        cleanup_count: self.cleanups.len(),
+                        condition: Slot::new(tag),
+                    };
+                    self.handlers.push(handler);
+                }
                 op::Nth => {
                     let list = self.env.stack.pop(cx);
                     let top = self.env.stack.top();
@@ -507,7 +629,7 @@ impl<'ob> RootedVM<'_, '_, '_> {
                     let newlet = self.env.stack.pop(cx);
                     let idx = self.env.stack.pop(cx);
                     let top = self.env.stack.top();
-                    top.set(data::aset(top.bind(cx), idx.try_into()?, newlet)?);
+                    top.set(data::aset(top.bind(cx), idx.try_into()?, newlet, cx)?);
                 }
                 op::SymbolValue => {
                     let top = self.env.stack.top().bind_as(cx)?;
@@ -630,42 +752,42 @@ impl<'ob> RootedVM<'_, '_, '_> {
                 op::Widen => todo!("Widen bytecode"),
                 op::EndOfLine => todo!("EndOfLine bytecode"),
                 op::ConstantN2 => {
-                    let idx = self.pc.arg2();
-                    let cnst = self.get_const(idx.into(), cx);
+                    let idx = self.arg2(cx)?;
+                    let cnst = self.get_const(idx.into(), cx)?;
                     self.env.stack.push(cnst);
                 }
                 op::Goto => {
-                    let offset = self.pc.arg2();
-                    self.pc.goto(offset);
+                    let offset = self.arg2(cx)?;
+                    self.goto(offset, cx)?;
                 }
                 op::GotoIfNil => {
                     let cond = self.env.stack.pop(cx);
-                    let offset = self.pc.arg2();
+                    let offset = self.arg2(cx)?;
                     if cond.is_nil() {
-                        self.pc.goto(offset);
+                        self.goto(offset, cx)?;
                     }
                 }
                 op::GotoIfNonNil => {
                     let cond = self.env.stack.pop(cx);
-                    let offset = self.pc.arg2();
+                    let offset = self.arg2(cx)?;
                     if !cond.is_nil() {
-                        self.pc.goto(offset);
+                        self.goto(offset, cx)?;
                     }
                 }
                 op::GotoIfNilElsePop => {
-                    let offset = self.pc.arg2();
+                    let offset = self.arg2(cx)?;
                     if self.env.stack[0].bind(cx).is_nil() {
-                        self.pc.goto(offset);
+                        self.goto(offset, cx)?;
                     } else {
                         self.env.stack.pop(cx);
                     }
                 }
                 op::GotoIfNonNilElsePop => {
-                    let offset = self.pc.arg2();
+                    let offset = self.arg2(cx)?;
                     if self.env.stack[0].bind(cx).is_nil() {
                         self.env.stack.pop(cx);
                     } else {
-                        self.pc.goto(offset);
+                        self.goto(offset, cx)?;
                     }
                 }
                 op::Return => {
@@ -683,7 +805,7 @@ impl<'ob> RootedVM<'_, '_, '_> {
                     self.env.stack.pop(cx);
                 }
                 op::DiscardN => {
-                    let arg = self.pc.arg1();
+                    let arg = self.arg1(cx)?;
                     let cur_len = self.env.stack.len();
                     let keep_tos = (arg & 0x80) != 0;
                     let count = (arg & 0x7F) as usize;
@@ -701,7 +823,12 @@ impl<'ob> RootedVM<'_, '_, '_> {
                 }
                 op::SaveExcursion => todo!("SaveExcursion bytecode"),
                 op::SaveRestriction => todo!("SaveRestriction bytecode"),
-                op::UnwindProtect => todo!("UnwindProtect bytecode"),
+                op::UnwindProtect => {
+                    let cleanup = self.env.stack.pop(cx);
+                    let depth = self.env.binding_depth();
+                    self.cleanups.push(cleanup);
+                    self.cleanup_depths.push(depth);
+                }
                 op::SetMarker => todo!("SetMarker bytecode"),
                 op::MatchBeginning => todo!("MatchBeginning bytecode"),
                 op::MatchEnd => todo!("MatchEnd bytecode"),
@@ -736,7 +863,7 @@ impl<'ob> RootedVM<'_, '_, '_> {
                 }
                 op::Nreverse => {
                     let elt = self.env.stack.top();
-                    elt.set(fns::nreverse(elt.bind_as(cx)?)?);
+                    elt.set(fns::nreverse(elt.bind(cx), cx)?);
                 }
                 op::Setcar => {
                     let newcar = self.env.stack.pop(cx);
@@ -772,7 +899,7 @@ impl<'ob> RootedVM<'_, '_, '_> {
                     top.set(data::integerp(top.bind(cx)));
                 }
                 op::ListN => {
-                    let size = self.pc.arg1() as usize;
+                    let size = self.arg1(cx)? as usize;
                     let slice = Rt::bind_slice(&self.env.stack[..size], cx);
                     let list = alloc::list(slice, cx);
                     let len = self.env.stack.len();
@@ -790,7 +917,7 @@ impl<'ob> RootedVM<'_, '_, '_> {
                         let ObjectType::Int(offset) = offset.untag() else {
                             unreachable!("switch value was not a int")
                         };
-                        self.pc.goto(offset as u16);
+                        self.goto(offset as u16, cx)?;
                     }
                 }
                 op::Constant0
@@ -858,7 +985,7 @@ impl<'ob> RootedVM<'_, '_, '_> {
                 | op::Constant62
                 | op::Constant63 => {
                     let idx = (op as u8) - (op::Constant0 as u8);
-                    let cnst = self.get_const(idx as usize, cx);
+                    let cnst = self.get_const(idx as usize, cx)?;
                     self.env.stack.push(cnst);
                 }
             }
@@ -893,6 +1020,157 @@ fn fetch_bytecode(_object: Object) {
     // TODO: Implement
 }
 
+/// Check that `codes` is well formed before it can be run: every opcode is
+/// known and has all of its operands, jumps land on the start of an
+/// instruction, and no instruction pops more values than are on the stack when
+/// starting with the arguments described by `args`. Constant indices are
+/// checked when the code runs, because the byte compiler creates functions
+/// whose constants are filled in later.
+///
+/// A `Switch` jumps through a hash table pushed by the constant instruction
+/// before it. The targets in that table are checked like any other jump when
+/// the table is in `constants`. If it is not there yet, code reached only
+/// through the switch is not checked.
+pub(crate) fn verify(codes: &[u8], constants: &[Object], args: FnArgs) -> Result<()> {
+    use opcode::OpCode as op;
+    // The decoded instruction starting at each offset
+    let mut instructions = vec![None; codes.len()];
+    // The offset of the instruction laid out before each instruction
+    let mut previous = vec![None; codes.len()];
+    let mut last = None;
+    let mut idx = 0;
+    while idx < codes.len() {
+        let offset = idx;
+        previous[offset] = last.replace(offset);
+        let byte = codes[idx];
+        idx += 1;
+        let Ok(op) = opcode::OpCode::try_from(byte) else {
+            bail!("Invalid Bytecode: unknown opcode {byte} at {offset}")
+        };
+        let size = op.operand_size();
+        let Some(operand) = codes.get(idx..idx + size) else {
+            bail!("Invalid Bytecode: {op:?} at {offset} is missing its operand")
+        };
+        let operand = match *operand {
+            [] => 0,
+            [x] => usize::from(x),
+            [low, high, ..] => usize::from(u16::from_le_bytes([low, high])),
+        };
+        idx += size;
+        instructions[offset] = Some((op, operand));
+    }
+
+    let mut depths = vec![None; codes.len()];
+    let arg_cnt = usize::from(args.required + args.optional) + usize::from(args.rest);
+    let mut pending = vec![(None, 0, arg_cnt)];
+    while let Some((jump_from, offset, depth)) = pending.pop() {
+        let Some(instruction) = instructions.get(offset) else {
+            // Running off the end of the code is caught when it runs
+            ensure!(jump_from.is_none(), "Invalid Bytecode: jump to {offset} is out of range");
+            continue;
+        };
+        let Some((op, operand)) = *instruction else {
+            let from = jump_from.unwrap_or_default();
+            bail!("Invalid Bytecode: jump at {from} to {offset} is not the start of an instruction")
+        };
+        if let Some(old) = depths[offset].replace(depth) {
+            ensure!(
+                old == depth,
+                "Invalid Bytecode: {op:?} at {offset} is reached with {old} and {depth} values on the stack"
+            );
+            continue;
+        }
+        let (popped, pushed) = op.stack_effect(operand);
+        ensure!(
+            popped <= depth,
+            "Invalid Bytecode: {op:?} at {offset} needs {popped} values on the stack, but there are only {depth}"
+        );
+        let after = depth - popped + pushed;
+        let next = offset + 1 + op.operand_size();
+        let jump = Some(offset);
+        match op {
+            op::Return => {}
+            op::Goto => pending.push((jump, operand, after)),
+            op::GotoIfNilElsePop | op::GotoIfNonNilElsePop => {
+                pending.extend([(jump, operand, depth), (None, next, after)]);
+            }
+            // The handler is entered with the error on the stack
+            op::PushCondtionCase | op::PushCatch => {
+                pending.extend([(jump, operand, after + 1), (None, next, after)]);
+            }
+            op::GotoIfNil | op::GotoIfNonNil => {
+                pending.extend([(jump, operand, after), (None, next, after)]);
+            }
+            op::Switch => {
+                let table = previous[offset]
+                    .and_then(|prev| instructions[prev])
+                    .filter(|(prev, _)| {
+                        matches!(prev, op::ConstantN2) || *prev as u8 >= op::Constant0 as u8
+                    })
+                    .and_then(|(prev, operand)| prev.constant_index(operand))
+                    .and_then(|i| constants.get(i));
+                if let Some(ObjectType::HashTable(table)) = table.map(|x| x.untag()) {
+                    for i in 0..table.len() {
+                        let (_, target) = table.get_index(i).unwrap();
+                        let ObjectType::Int(x) = target.untag() else {
+                            bail!("Invalid Bytecode: switch at {offset} has jump target {target}")
+                        };
+                        let Ok(target) = u16::try_from(x) else {
+                            bail!("Invalid Bytecode: switch at {offset} has jump target {target}")
+                        };
+                        pending.push((jump, usize::from(target), after));
+                    }
+                }
+                pending.push((None, next, after));
+            }
+            _ => pending.push((None, next, after)),
+        }
+    }
+    Ok(())
+}
+
+/// Render the bytecode of `func` as text, one instruction per line. Each line
+/// has the byte offset, the opcode, any operand, and the constant the
+/// instruction refers to.
+pub(crate) fn disassemble_to_string(func: &ByteFn) -> String {
+    use std::fmt::Write as _;
+    let codes = func.codes();
+    let consts = func.consts();
+    let mut out = String::new();
+    let mut idx = 0;
+    while idx < codes.len() {
+        let offset = idx;
+        let byte = codes[idx];
+        idx += 1;
+        let op = match opcode::OpCode::try_from(byte) {
+            Ok(op) => op,
+            Err(_) => {
+                writeln!(out, "{offset:>4} <invalid opcode {byte}>").unwrap();
+                continue;
+            }
+        };
+        write!(out, "{offset:>4} {op:?}").unwrap();
+        let operand = match op.operand_size() {
+            0 => 0,
+            size if idx + size > codes.len() => {
+                writeln!(out, " <truncated>").unwrap();
+                break;
+            }
+            1 => usize::from(codes[idx]),
+            _ => usize::from(u16::from_le_bytes([codes[idx], codes[idx + 1]])),
+        };
+        if op.operand_size() > 0 {
+            idx += op.operand_size();
+            write!(out, " {operand}").unwrap();
+        }
+        if let Some(constant) = op.constant_index(operand).and_then(|i| consts.get(i)) {
+            write!(out, " ; {constant}").unwrap();
+        }
+        out.push('\n');
+    }
+    out
+}
+
 pub(crate) fn call<'ob>(
     func: &Rto<&ByteFn>,
     arg_cnt: usize,
@@ -902,15 +1180,32 @@ pub(crate) fn call<'ob>(
 ) -> EvalResult<'ob> {
     frame.stack.set_depth(func.bind(cx).depth);
     let func = func.bind(cx);
+    if cfg!(feature = "debug_bytecode") && crate::debug::debug_enabled() {
+        println!("{name}:\n{}", disassemble_to_string(func));
+    }
     let vm = VM {
-        pc: ProgramCounter::new(func.codes()),
+        pc: ProgramCounter::default(),
         func: Slot::new(func),
+        binding_depth: frame.binding_depth(),
         env: frame,
         handlers: Vec::new(),
+        cleanups: Vec::new(),
+        cleanup_depths: Vec::new(),
     };
     root!(vm, cx);
     vm.prepare_lisp_args(func, arg_cnt, name, cx)?;
-    vm.run(cx).map_err(|e| e.add_trace(name, vm.env.stack.current_args()))
+    let frame = vm.env.stack.current_frame();
+    let len = vm.env.stack.len();
+    match vm.run(cx) {
+        Ok(x) => Ok(rebind!(x, cx)),
+        Err(e) => {
+            // An uncaught error can leave inner frames and temporaries behind.
+            // Return to the state right after the arguments were set up.
+            vm.unwind(frame, cx);
+            vm.env.stack.truncate(len);
+            Err(e.add_trace(name, vm.env.stack.current_args()))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -998,6 +1293,17 @@ mod test {
         assert_eq!(val, expect);
     }
 
+    /// Create a byte-code function without checking the code with [`verify`].
+    fn make_unverified<'ob>(
+        codes: &[u8],
+        constants: Vec<Object<'ob>>,
+        cx: &'ob Context,
+    ) -> &'ob ByteFn {
+        let constants = constants.into_obj(cx).untag();
+        let args = crate::core::object::FnArgs::from_arg_spec(0).unwrap();
+        unsafe { ByteFn::make(codes, constants, args, 10) }.into_obj(cx).untag()
+    }
+
     #[test]
     fn test_basic() {
         use OpCode::*;
@@ -1042,6 +1348,259 @@ mod test {
         check_bytecode!(bytecode, [0], 0, cx);
     }
 
+    #[test]
+    fn test_malformed_bytecode() {
+        use OpCode::*;
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        let check_error = |bytecode: &Rto<&ByteFn>, cx: &mut Context| {
+            root!(env, new(Env), cx);
+            let frame = &mut CallFrame::new(env);
+            frame.finalize_arguments();
+            let err = call(bytecode, 0, "test", frame, cx).unwrap_err();
+            assert!(err.to_string().contains("Invalid Bytecode"), "{err}");
+        };
+        // runs off the end of the code
+        make_bytecode!(bytecode, 0, [Constant0], [5], cx);
+        check_error(bytecode, cx);
+        // These are rejected by `make-byte-code`, so create them directly to
+        // check that the VM also catches them.
+        // argument is cut off
+        let bytecode = make_unverified(&[Constant0 as u8, Goto as u8, 0x00], vec![5.into()], cx);
+        root!(bytecode, cx);
+        check_error(bytecode, cx);
+        // jump outside of the code
+        let codes = [Constant0 as u8, Goto as u8, 0x10, 0x00, Return as u8];
+        let bytecode = make_unverified(&codes, vec![5.into()], cx);
+        root!(bytecode, cx);
+        check_error(bytecode, cx);
+    }
+
+    #[test]
+    fn test_stack_effect() {
+        use OpCode::*;
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        // leaves (1 . 2), 1, and (1 3) on the stack
+        let codes = [
+            Constant0 as u8,
+            Constant1 as u8,
+            Cons as u8,
+            Constant0 as u8,
+            Duplicate as u8,
+            Constant1 as u8,
+            Plus as u8,
+            StackRef1 as u8,
+            StackRefN as u8,
+            0x01,
+            List2 as u8,
+            DiscardN as u8,
+            0x81,
+        ];
+        let mut idx = 0;
+        let mut depth = 0;
+        while idx < codes.len() {
+            let op = OpCode::try_from(codes[idx]).unwrap();
+            let operand = if op.operand_size() == 1 { usize::from(codes[idx + 1]) } else { 0 };
+            let (popped, pushed) = op.stack_effect(operand);
+            assert!(popped <= depth, "{op:?} underflows");
+            depth = depth - popped + pushed;
+            idx += 1 + op.operand_size();
+        }
+        assert_eq!(depth, 3);
+        // collect everything left on the stack to compare with the computed
+        // depth
+        let mut codes = codes.to_vec();
+        codes.extend([ListN as u8, depth as u8, Return as u8]);
+        let bytecode = make_unverified(&codes, vec![1.into(), 2.into()], cx);
+        root!(bytecode, cx);
+        let expect = list!(crate::core::cons::Cons::new(1, 2, cx), 1, list!(1, 3; cx); cx);
+        root!(expect, cx);
+        root!(args, new(Vec), cx);
+        check_bytecode_internal(args, bytecode, expect, cx);
+
+        assert_eq!(Call2.stack_effect(0), (3, 1));
+        assert_eq!(CallN.stack_effect(7), (8, 1));
+        assert_eq!(DiscardN.stack_effect(0x03), (3, 0));
+        assert_eq!(DiscardN.stack_effect(0x83), (4, 1));
+        assert_eq!(StackSetN.stack_effect(2), (3, 2));
+        // every opcode has a stack effect
+        for byte in 0..=u8::MAX {
+            if let Ok(op) = OpCode::try_from(byte) {
+                op.stack_effect(0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_bytecode() {
+        use OpCode::*;
+        let no_args = FnArgs::from_arg_spec(0).unwrap();
+        let one_arg = FnArgs::from_arg_spec(257).unwrap();
+        // (lambda (x) (if x 2 3))
+        let mut codes = [
+            Duplicate as u8,
+            GotoIfNil as u8,
+            0x06,
+            0x00,
+            Constant0 as u8,
+            Return as u8,
+            Constant1 as u8,
+            Return as u8,
+        ];
+        assert!(verify(&codes, &[], one_arg).is_ok());
+        // jump into the middle of an instruction
+        codes[2] = 0x03;
+        assert!(verify(&codes, &[], one_arg).is_err());
+        // jump past the end
+        codes[2] = 0x08;
+        assert!(verify(&codes, &[], one_arg).is_err());
+        // missing operand
+        assert!(verify(&[Constant0 as u8, Goto as u8, 0x00], &[], no_args).is_err());
+        assert!(verify(&[VarRefN2 as u8, 0x00], &[], no_args).is_err());
+        // unknown opcode
+        assert!(verify(&[51], &[], no_args).is_err());
+        // stack underflow
+        assert!(verify(&[Constant0 as u8, Plus as u8, Return as u8], &[], no_args).is_err());
+        assert!(verify(&[Duplicate as u8, Plus as u8, Return as u8], &[], one_arg).is_ok());
+        assert!(verify(&[StackRef1 as u8, Return as u8], &[], one_arg).is_err());
+        // the handler starts with the error on the stack
+        let codes = [
+            Constant0 as u8,
+            PushCondtionCase as u8,
+            0x06,
+            0x00,
+            Constant0 as u8,
+            Return as u8,
+            Return as u8,
+        ];
+        assert!(verify(&codes, &[], no_args).is_ok());
+        // make-byte-code rejects invalid code
+        crate::interpreter::assert_lisp(
+            "(condition-case nil (make-byte-code 0 (unibyte-string 130 10 0 135) [] 1) (error 'invalid))",
+            "invalid",
+        );
+        // a branch that reaches an instruction with fewer values on the stack
+        crate::interpreter::assert_lisp(
+            "(condition-case nil (make-byte-code 0 (unibyte-string 192 131 6 0 192 192 92 135) [nil] 2) (error 'invalid))",
+            "invalid",
+        );
+        // constant indices are checked when the code is run
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        make_bytecode!(bytecode, 0, [Constant0, Constant1, Return], [5], cx);
+        root!(env, new(Env), cx);
+        let frame = &mut CallFrame::new(env);
+        frame.finalize_arguments();
+        let err = call(bytecode, 0, "test", frame, cx).unwrap_err();
+        assert!(err.to_string().contains("constant 1 is out of range"), "{err}");
+    }
+
+    #[test]
+    fn test_verify_switch() {
+        use crate::interpreter::assert_lisp;
+        // (lambda (x) (pcase x (1 'one) (_ 'other)))
+        let switch = |code: &str, target: &str, table: &str| {
+            format!(
+                "(let ((h (make-hash-table)))
+                   (puthash 1 {target} h)
+                   (condition-case nil
+                       (list (funcall (make-byte-code 257 (unibyte-string {code}) (vector {table} 'other 'one) 3) 1)
+                             (funcall (make-byte-code 257 (unibyte-string {code}) (vector {table} 'other 'one) 3) 2))
+                     (error 'invalid)))"
+            )
+        };
+        let valid = "0 192 183 193 135 194 135";
+        assert_lisp(&switch(valid, "5", "h"), "(one other)");
+        // jump targets in the table are checked
+        assert_lisp(&switch(valid, "50", "h"), "invalid");
+        assert_lisp(&switch(valid, "-1", "h"), "invalid");
+        assert_lisp(&switch(valid, "'a", "h"), "invalid");
+        // the code at the target underflows the stack
+        let underflow = "0 192 183 193 135 92 135";
+        assert_lisp(&switch(underflow, "5", "h"), "invalid");
+        // without the table the switch targets can't be followed
+        let unchecked = format!(
+            "(functionp (make-byte-code 257 (unibyte-string {underflow}) (vector nil 'other 'one) 3))"
+        );
+        assert_lisp(&unchecked, "t");
+    }
+
+    #[test]
+    fn test_constant_identity() {
+        use OpCode::*;
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        sym::init_symbols();
+        // (lambda () (eq '(1 2) '(1 2)))
+        make_bytecode!(
+            bytecode,
+            0,
+            [Constant0, Constant1, Eq, Return],
+            [list![1, 2; cx], list![1, 2; cx]],
+            cx
+        );
+        check_bytecode!(bytecode, [], false, cx);
+        // (lambda () (equal '(1 2) '(1 2)))
+        make_bytecode!(
+            bytecode,
+            0,
+            [Constant0, Constant1, Equal, Return],
+            [list![1, 2; cx], list![1, 2; cx]],
+            cx
+        );
+        check_bytecode!(bytecode, [], true, cx);
+        // (lambda () (progn (setcar '(1 2) 5) '(1 2)))
+        make_bytecode!(
+            bytecode,
+            0,
+            [Constant0, Constant1, Constant2, Call2, Discard, Constant3, Return],
+            [sym::SETCAR, list![1, 2; cx], 5, list![1, 2; cx]],
+            cx
+        );
+        let expect = list![1, 2; cx];
+        root!(expect, cx);
+        check_bytecode!(bytecode, [], expect, cx);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        use OpCode::*;
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        sym::init_symbols();
+        // (lambda (x) (if x (symbol-name 'car) 3))
+        make_bytecode!(
+            bytecode,
+            257,
+            [
+                Duplicate, GotoIfNil, 0x08, 0x00, Constant0, Constant1, Call1, Return, Constant2,
+                Return
+            ],
+            [sym::SYMBOL_NAME, sym::CAR, 3],
+            cx
+        );
+        let expect = "   0 Duplicate
+   1 GotoIfNil 8
+   4 Constant0 ; symbol-name
+   5 Constant1 ; car
+   6 Call1
+   7 Return
+   8 Constant2 ; 3
+   9 Return
+";
+        assert_eq!(disassemble_to_string(bytecode.bind(cx)), expect);
+
+        let codes = [VarRefN as u8, 0x01, ListN as u8, 0x02, ConstantN2 as u8];
+        let bytecode = make_unverified(&codes, vec![1.into(), cx.add("two")], cx);
+        root!(bytecode, cx);
+        let expect = "   0 VarRefN 1 ; \"two\"
+   2 ListN 2
+   4 ConstantN2 <truncated>
+";
+        assert_eq!(disassemble_to_string(bytecode.bind(cx)), expect);
+    }
+
     #[test]
     fn test_bytecode_call() {
         use OpCode::*;
@@ -1248,4 +1807,162 @@ mod test {
         root!(inner, cx);
         check_bytecode!(outer, [inner], 7, cx);
     }
+
+    #[test]
+    fn test_catch() {
+        use crate::interpreter::assert_lisp;
+        // (lambda (f) (+ 1 (catch 7 (while t (+ 10 20 (funcall f))))))
+        // The throw happens with the loop's temporaries still on the stack
+        let looping = "(make-byte-code 257 (unibyte-string 192 193 50 15 0 194 195 3 32 92 92 136 130 5 0 92 135) [1 7 10 20] 6)";
+        let call = |f: &str| format!("(funcall {looping} #'(lambda () {f}))");
+        assert_lisp(&call("(throw 7 5)"), "6");
+        assert_lisp(&format!("(catch 8 {})", call("(throw 8 9)")), "9");
+        assert_lisp(&format!("(catch 7 (list (catch 8 {}) 2))", call("(throw 7 3)")), "(4 2)");
+        // the catch is removed once it is done
+        let no_catch = "(condition-case nil (throw 7 1) (error 'no-catch))";
+        assert_lisp(&format!("(progn {} {no_catch})", call("(throw 7 5)")), "no-catch");
+        // (lambda () (+ 1 (catch 7 41)))
+        let normal = "(make-byte-code 0 (unibyte-string 192 193 50 7 0 194 48 92 135) [1 7 41] 4)";
+        assert_lisp(&format!("(funcall {normal})"), "42");
+        assert_lisp(&format!("(progn (funcall {normal}) {no_catch})"), "no-catch");
+    }
+
+    #[test]
+    fn test_throw_across_frames() {
+        use crate::interpreter::assert_lisp;
+        // (lambda (x) (throw TAG x))
+        let thrower = |tag: u8| {
+            format!("(make-byte-code 257 (unibyte-string 192 193 2 34 135) [throw {tag}] 5)")
+        };
+        // (lambda (g x cleanup) (unwind-protect (funcall g x) (funcall cleanup)))
+        let middle = "(make-byte-code 771 (unibyte-string 0 142 2 2 33 41 135) [] 6)";
+        // (lambda (m g x cleanup) (+ 1 (catch 7 (funcall m g x cleanup))))
+        let outer =
+            "(make-byte-code 1028 (unibyte-string 192 193 50 11 0 4 4 4 4 35 48 92 135) [1 7] 10)";
+        let cleanup = "#'(lambda () (setq throw-log (cons 'clean throw-log)))";
+        let run = |form: &str| format!("(progn (setq throw-log nil) (list {form} throw-log))");
+        // the throw unwinds three bytecode frames and runs the cleanup between them
+        let caught = format!("(funcall {outer} {middle} {} 41 {cleanup})", thrower(7));
+        assert_lisp(&run(&caught), "(42 (clean))");
+        // a tag the bytecode does not catch goes past it to the interpreter
+        let passed = format!("(catch 8 (funcall {outer} {middle} {} 41 {cleanup}))", thrower(8));
+        assert_lisp(&run(&passed), "(41 (clean))");
+        // without a catch it is an error, and cleanups still run
+        let uncaught = format!("(funcall {middle} {} 1 {cleanup})", thrower(7));
+        assert_lisp(
+            &run(&format!("(condition-case nil {uncaught} (error 'no-catch))")),
+            "(no-catch (clean))",
+        );
+    }
+
+    #[test]
+    fn test_unwind_protect() {
+        use crate::interpreter::assert_lisp;
+        // (lambda (body cleanup) (unwind-protect (funcall body) (funcall cleanup)))
+        let protect = "(make-byte-code 514 (unibyte-string 0 142 1 32 41 135) [] 3)";
+        let log = |name: &str| format!("#'(lambda () (setq up-log (cons '{name} up-log)))");
+        let call = |body: &str, cleanup: &str| format!("(funcall {protect} {body} {cleanup})");
+        let run = |form: &str| format!("(progn (setq up-log nil) (list {form} up-log))");
+        // normal return
+        assert_lisp(&run(&call("#'(lambda () 'value)", &log("clean"))), "(value (clean))");
+        // error in body
+        let signal = |sym: &str| format!("#'(lambda () (signal '{sym} nil))");
+        let body_error = call(&signal("arith-error"), &log("clean"));
+        assert_lisp(
+            &run(&format!("(condition-case err {body_error} (error (car err)))")),
+            "(arith-error (clean))",
+        );
+        // error in cleanup replaces the error from the body
+        let cleanup_error = call(&signal("arith-error"), &signal("wrong-type-argument"));
+        assert_lisp(
+            &format!("(condition-case err {cleanup_error} (error (car err)))"),
+            "wrong-type-argument",
+        );
+        // nested cleanups run innermost first, even when the inner one errors
+        let inner_cleanup =
+            "#'(lambda () (setq up-log (cons 'inner up-log)) (signal 'wrong-type-argument nil))";
+        let inner = call(&signal("arith-error"), inner_cleanup);
+        let nested = call(&format!("#'(lambda () {inner})"), &log("outer"));
+        assert_lisp(
+            &run(&format!("(condition-case err {nested} (error (car err)))")),
+            "(wrong-type-argument (outer inner))",
+        );
+        // throw
+        let thrown = call("#'(lambda () (throw 7 1))", &log("clean"));
+        assert_lisp(&run(&format!("(catch 7 {thrown})")), "(1 (clean))");
+        // a handler in the same function runs the cleanup before it is entered
+        // (lambda (body cleanup)
+        //   (condition-case nil
+        //       (unwind-protect (funcall body) (funcall cleanup))
+        //     (error 'caught)))
+        let handled = "(make-byte-code 514 (unibyte-string 192 49 11 0 0 142 1 32 41 48 135 136 193 135) [(error) caught] 5)";
+        assert_lisp(
+            &run(&format!("(funcall {handled} {} {})", signal("error"), log("clean"))),
+            "(caught (clean))",
+        );
+        assert_lisp(
+            &run(&format!("(funcall {handled} #'(lambda () 'value) {})", log("clean"))),
+            "(value (clean))",
+        );
+        // cleanups are unwound in order with dynamic bindings
+        // (lambda (cleanup) (unwind-protect (let ((up-test-var 2)) up-test-var) (funcall cleanup)))
+        let dynamic =
+            "(make-byte-code 257 (unibyte-string 0 142 192 25 9 42 135) [2 up-test-var] 2)";
+        assert_lisp(
+            &run(&format!(
+                "(funcall {dynamic} #'(lambda () (setq up-log (boundp 'up-test-var))))"
+            )),
+            "(2 nil)",
+        );
+        // older code passes a list of forms instead of a function
+        let forms =
+            "(make-byte-code 0 (unibyte-string 192 142 193 41 135) [((setq up-log 'forms)) 5] 2)";
+        assert_lisp(&run(&format!("(funcall {forms})")), "(5 forms)");
+    }
+
+    #[test]
+    fn test_make_closure() {
+        use OpCode as O;
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        // The first 7 constants are placeholders for the captured variables.
+        // Each capture is a distinct power of two, so the sum shows that every
+        // slot was read back.
+        make_bytecode!(
+            prototype,
+            0,
+            [
+                O::Constant0,
+                O::Constant1,
+                O::Plus,
+                O::Constant2,
+                O::Plus,
+                O::Constant3,
+                O::Plus,
+                O::Constant4,
+                O::Plus,
+                O::Constant5,
+                O::Plus,
+                O::Constant6,
+                O::Plus,
+                O::Constant7,
+                O::Plus,
+                O::Return
+            ],
+            [0, 0, 0, 0, 0, 0, 0, 100],
+            cx
+        );
+        let vars: Vec<Object> = (0..7).map(|i| cx.add(1 << i)).collect();
+        let closure = crate::alloc::make_closure(prototype.bind(cx), &vars, cx).unwrap().untag();
+        for (cnst, var) in closure.consts().iter().zip(&vars) {
+            assert_eq!(cnst, var);
+        }
+        root!(closure, cx);
+        check_bytecode!(closure, [], 227, cx);
+        // the prototype's constants are left alone
+        check_bytecode!(prototype, [], 100, cx);
+
+        let vars: Vec<Object> = (0..9).map(|i| cx.add(i)).collect();
+        assert!(crate::alloc::make_closure(prototype.bind(cx), &vars, cx).is_err());
+    }
 }
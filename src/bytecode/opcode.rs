@@ -261,3 +261,201 @@ pub(crate) enum OpCode {
     Constant62 = 254,
     Constant63 = 255,
 }
+
+impl OpCode {
+    /// The number of operand bytes that follow this opcode in the bytecode
+    /// stream.
+    pub(crate) const fn operand_size(self) -> usize {
+        use OpCode as O;
+        match self {
+            O::StackRefN
+            | O::StackSetN
+            | O::VarRefN
+            | O::VarSetN
+            | O::VarBindN
+            | O::CallN
+            | O::UnbindN
+            | O::DiscardN
+            | O::ListN
+            | O::ConcatN
+            | O::InsertN => 1,
+            O::StackRefN2
+            | O::StackSetN2
+            | O::VarRefN2
+            | O::VarSetN2
+            | O::VarBindN2
+            | O::CallN2
+            | O::UnbindN2
+            | O::ConstantN2
+            | O::Goto
+            | O::GotoIfNil
+            | O::GotoIfNonNil
+            | O::GotoIfNilElsePop
+            | O::GotoIfNonNilElsePop
+            | O::PushCondtionCase
+            | O::PushCatch => 2,
+            _ => 0,
+        }
+    }
+
+    /// The number of values this opcode pops from the stack and the number it
+    /// pushes, as `(popped, pushed)`. `operand` is the value of the operand
+    /// bytes following the opcode. Values that are read in place, like the
+    /// ones under the top for `StackRefN`, count as popped and pushed again so
+    /// the stack depth the instruction needs is included. For the `ElsePop`
+    /// jumps this is the effect when the jump is not taken.
+    pub(crate) fn stack_effect(self, operand: usize) -> (usize, usize) {
+        use OpCode as O;
+        let code = self as u8;
+        let offset_from = |base: OpCode| usize::from(code - base as u8);
+        match self {
+            _ if code >= O::Constant0 as u8 => (0, 1),
+            _ if (O::StackRef0 as u8..=O::StackRef5 as u8).contains(&code) => {
+                let n = offset_from(O::StackRef0);
+                (n + 1, n + 2)
+            }
+            O::StackRefN | O::StackRefN2 => (operand + 1, operand + 2),
+            O::StackSetN | O::StackSetN2 => (operand + 1, operand),
+            _ if (O::VarRef0 as u8..=O::VarRefN2 as u8).contains(&code) => (0, 1),
+            _ if (O::VarSet0 as u8..=O::VarBindN2 as u8).contains(&code) => (1, 0),
+            _ if (O::Call0 as u8..=O::Call5 as u8).contains(&code) => {
+                (offset_from(O::Call0) + 1, 1)
+            }
+            O::CallN | O::CallN2 => (operand + 1, 1),
+            _ if (O::Unbind0 as u8..=O::UnbindN2 as u8).contains(&code) => (0, 0),
+            O::PopHandler
+            | O::SaveCurrentBuffer1
+            | O::Goto
+            | O::SaveExcursion
+            | O::SaveRestriction => (0, 0),
+            O::PushCondtionCase
+            | O::PushCatch
+            | O::GotoIfNil
+            | O::GotoIfNonNil
+            | O::GotoIfNilElsePop
+            | O::GotoIfNonNilElsePop
+            | O::Return
+            | O::Discard
+            | O::UnwindProtect => (1, 0),
+            O::Point
+            | O::PointMax
+            | O::PointMin
+            | O::FollowingChar
+            | O::PrecedingChar
+            | O::CurrentColumn
+            | O::EndOfLineP
+            | O::EndOfBufferP
+            | O::BeginningOfLineP
+            | O::BeginningOfBufferP
+            | O::CurrentBuffer
+            | O::Widen
+            | O::ConstantN2 => (0, 1),
+            O::Duplicate => (1, 2),
+            O::Symbolp
+            | O::Consp
+            | O::Stringp
+            | O::Listp
+            | O::Not
+            | O::Car
+            | O::Cdr
+            | O::List1
+            | O::Length
+            | O::SymbolValue
+            | O::SymbolFunction
+            | O::Sub1
+            | O::Add1
+            | O::Negate
+            | O::GotoChar
+            | O::Insert
+            | O::CharAfter
+            | O::IndentTo
+            | O::SetBuffer
+            | O::ForwardChar
+            | O::ForwardWord
+            | O::ForwardLine
+            | O::CharSyntax
+            | O::EndOfLine
+            | O::MatchBeginning
+            | O::MatchEnd
+            | O::Upcase
+            | O::Downcase
+            | O::Nreverse
+            | O::CarSafe
+            | O::CdrSafe
+            | O::Numberp
+            | O::Integerp => (1, 1),
+            O::Nth
+            | O::Eq
+            | O::Memq
+            | O::Cons
+            | O::List2
+            | O::Aref
+            | O::Set
+            | O::Fset
+            | O::Get
+            | O::Concat2
+            | O::EqlSign
+            | O::GreaterThan
+            | O::LessThan
+            | O::LessThanOrEqual
+            | O::GreaterThanOrEqual
+            | O::Diff
+            | O::Plus
+            | O::Max
+            | O::Min
+            | O::Multiply
+            | O::SkipCharsForward
+            | O::SkipCharsBackward
+            | O::BufferSubstring
+            | O::DeleteRegion
+            | O::NarrowToRegion
+            | O::StringEqlSign
+            | O::StringLessThan
+            | O::Equal
+            | O::Nthcdr
+            | O::Elt
+            | O::Member
+            | O::Assq
+            | O::Setcar
+            | O::Setcdr
+            | O::Nconc
+            | O::Quo
+            | O::Rem => (2, 1),
+            O::List3 | O::Aset | O::Substring | O::Concat3 | O::SetMarker => (3, 1),
+            O::List4 | O::Concat4 => (4, 1),
+            O::ListN | O::ConcatN | O::InsertN => (operand, 1),
+            O::DiscardN if operand & 0x80 != 0 => ((operand & 0x7F) + 1, 1),
+            O::DiscardN => (operand & 0x7F, 0),
+            O::Switch => (2, 0),
+            _ => unreachable!("stack effect of {self:?} not covered"),
+        }
+    }
+
+    /// The index into the constant vector that this opcode refers to, if any.
+    /// `operand` is the value of the operand bytes following the opcode.
+    pub(crate) fn constant_index(self, operand: usize) -> Option<usize> {
+        use OpCode as O;
+        let code = self as u8;
+        let offset_from = |base: OpCode| usize::from(code - base as u8);
+        match self {
+            _ if code >= O::Constant0 as u8 => Some(offset_from(O::Constant0)),
+            _ if (O::VarRef0 as u8..=O::VarRef5 as u8).contains(&code) => {
+                Some(offset_from(O::VarRef0))
+            }
+            _ if (O::VarSet0 as u8..=O::VarSet5 as u8).contains(&code) => {
+                Some(offset_from(O::VarSet0))
+            }
+            _ if (O::VarBind0 as u8..=O::VarBind5 as u8).contains(&code) => {
+                Some(offset_from(O::VarBind0))
+            }
+            O::ConstantN2
+            | O::VarRefN
+            | O::VarRefN2
+            | O::VarSetN
+            | O::VarSetN2
+            | O::VarBindN
+            | O::VarBindN2 => Some(operand),
+            _ => None,
+        }
+    }
+}
@@ -1,11 +1,14 @@
 //! Character and string utilities.
 use crate::core::{
-    gc::Context,
+    env::{sym, Env},
+    gc::{Context, Rt},
     object::{int_to_char, Gc, Object, ObjectType, OptionalFlag},
 };
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use rune_macros::defun;
 
+defvar!(CASE_FOLD_SEARCH, true);
+
 #[defun]
 fn unibyte_string(bytes: &[Gc<i64>]) -> Result<Vec<u8>> {
     let unibyte: Result<Vec<u8>, _> = bytes.iter().map(|x| u8::try_from(x.untag())).collect();
@@ -32,6 +35,20 @@ fn characterp(obj: Object) -> bool {
     }
 }
 
+#[defun]
+fn char_equal(c1: i64, c2: i64, env: &Rt<Env>, cx: &Context) -> Result<bool> {
+    let c1 = int_to_char(c1)?;
+    let c2 = int_to_char(c2)?;
+    if c1 == c2 {
+        return Ok(true);
+    }
+    let fold_case = match env.vars.get(sym::CASE_FOLD_SEARCH) {
+        Some(val) => !val.bind(cx).is_nil(),
+        None => false,
+    };
+    Ok(fold_case && c1.to_lowercase().eq(c2.to_lowercase()))
+}
+
 #[defun]
 fn string(characters: &[Gc<i64>]) -> Result<String> {
     let string: Result<_, _> = characters.iter().map(|x| int_to_char(x.untag())).collect();
@@ -59,3 +76,149 @@ fn make_string<'ob>(
         Ok(cx.add(string))
     }
 }
+
+/// The number of columns `chr` takes up when displayed. This approximates the
+/// Emacs char-width-table: East Asian wide characters take two columns,
+/// control characters are shown as `^X`, and combining marks take none.
+fn display_width(chr: char) -> usize {
+    match u32::from(chr) {
+        0x09 => 8,
+        0x0A => 0,
+        0x00..=0x1F | 0x7F => 2,
+        0x80..=0x9F => 4,
+        0x0300..=0x036F | 0x200B..=0x200F | 0xFE00..=0xFE0F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+#[defun]
+fn char_width(chr: i64) -> Result<usize> {
+    Ok(display_width(int_to_char(chr)?))
+}
+
+#[defun]
+fn string_width(string: &str, from: Option<usize>, to: Option<usize>) -> Result<usize> {
+    let len = string.chars().count();
+    let from = from.unwrap_or(0);
+    let to = to.unwrap_or(len);
+    ensure!(from <= to && to <= len, "Args out of range: {string:?}, {from}, {to}");
+    Ok(string.chars().skip(from).take(to - from).map(display_width).sum())
+}
+
+/// Return the part of `string` that fits between `start_column` and
+/// `end_column` when displayed. If `padding` is a character, it fills the
+/// columns a wide character only partially covers and pads the result out to
+/// `end_column`. If `ellipsis` is non-nil and the string does not fit, the
+/// result ends with `ellipsis` (or "..." if it is not a string).
+#[defun]
+fn truncate_string_to_width(
+    string: &str,
+    end_column: usize,
+    start_column: Option<usize>,
+    padding: Option<i64>,
+    ellipsis: Option<Object>,
+) -> Result<String> {
+    let padding = padding.map(int_to_char).transpose()?;
+    let ellipsis = match ellipsis.map(|x| x.untag()) {
+        None | Some(ObjectType::NIL) => "",
+        Some(ObjectType::String(string)) => string,
+        Some(_) => "...",
+    };
+    let start_column = start_column.unwrap_or(0);
+    let total_width: usize = string.chars().map(display_width).sum();
+    let (ellipsis, end) = if total_width > end_column {
+        let ellipsis_width = ellipsis.chars().map(display_width).sum();
+        (ellipsis, end_column.saturating_sub(ellipsis_width))
+    } else {
+        ("", end_column)
+    };
+    let mut result = String::new();
+    let mut column = 0;
+    let mut chars = string.chars().peekable();
+    // Skip the characters before START-COLUMN
+    while let Some(&chr) = chars.peek() {
+        if column >= start_column {
+            break;
+        }
+        column += display_width(chr);
+        chars.next();
+    }
+    if let Some(pad) = padding {
+        result.extend((start_column..column.min(end)).map(|_| pad));
+    }
+    for chr in chars {
+        let width = display_width(chr);
+        if column + width > end {
+            break;
+        }
+        column += width;
+        result.push(chr);
+    }
+    if let Some(pad) = padding {
+        result.extend((column..end).map(|_| pad));
+    }
+    result.push_str(ellipsis);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::interpreter::assert_lisp;
+
+    #[test]
+    fn test_char_equal() {
+        assert_lisp("(char-equal ?a ?a)", "t");
+        assert_lisp("(char-equal ?a ?b)", "nil");
+        assert_lisp("(let ((case-fold-search t)) (char-equal ?a ?A))", "t");
+        assert_lisp("(let ((case-fold-search t)) (char-equal ?a ?B))", "nil");
+        assert_lisp("(let ((case-fold-search nil)) (char-equal ?a ?A))", "nil");
+        assert_lisp("(let ((case-fold-search nil)) (char-equal ?a ?a))", "t");
+    }
+
+    #[test]
+    #[allow(clippy::non_ascii_literal)]
+    fn test_string_width() {
+        assert_lisp(r#"(string-width "hello")"#, "5");
+        assert_lisp(r#"(string-width "")"#, "0");
+        assert_lisp(r#"(string-width "日本語")"#, "6");
+        assert_lisp(r#"(string-width "a日b")"#, "4");
+        assert_lisp(r#"(string-width "a日b" 1)"#, "3");
+        assert_lisp(r#"(string-width "a日b" 0 1)"#, "1");
+        assert_lisp("(string-width \"e\u{301}\")", "1");
+        assert_lisp("(char-width ?a)", "1");
+        assert_lisp(r"(char-width ?\t)", "8");
+        assert_lisp("(char-width 1)", "2");
+        assert_lisp(r#"(condition-case nil (string-width "ab" 0 3) (error 'range))"#, "range");
+    }
+
+    #[test]
+    #[allow(clippy::non_ascii_literal)]
+    fn test_truncate_string_to_width() {
+        assert_lisp(r#"(truncate-string-to-width "hello world" 5)"#, r#""hello""#);
+        assert_lisp(r#"(truncate-string-to-width "hi" 5)"#, r#""hi""#);
+        assert_lisp(r#"(truncate-string-to-width "hello world" 8 nil nil t)"#, r#""hello...""#);
+        assert_lisp(r#"(truncate-string-to-width "hello" 5 nil nil t)"#, r#""hello""#);
+        assert_lisp(r#"(truncate-string-to-width "hello world" 7 nil nil "~")"#, r#""hello ~""#);
+        assert_lisp(r#"(truncate-string-to-width "hello world" 8 3)"#, r#""lo wo""#);
+        assert_lisp(r#"(truncate-string-to-width "日本語" 4)"#, r#""日本""#);
+        assert_lisp(r#"(truncate-string-to-width "日本語" 5)"#, r#""日本""#);
+        assert_lisp(r#"(truncate-string-to-width "日本語" 5 nil ?.)"#, r#""日本.""#);
+        assert_lisp(r#"(truncate-string-to-width "日本語" 6 1 ?.)"#, r#"".本語""#);
+        assert_lisp(r#"(truncate-string-to-width "ab" 4 nil ?-)"#, r#""ab--""#);
+    }
+}
@@ -0,0 +1,133 @@
+//! Char-table functions.
+use crate::core::{
+    gc::Context,
+    object::{CharTable, CharTableInner, IntoObject, Object, ObjectType},
+};
+use anyhow::{bail, Result};
+use rune_macros::defun;
+
+/// Return a new char-table with subtype `purpose`. Every character maps to
+/// `init` until it is set.
+#[defun]
+fn make_char_table<'ob>(purpose: Object, init: Option<Object>, cx: &'ob Context) -> Object<'ob> {
+    let init = init.unwrap_or_default();
+    unsafe { CharTableInner::new(purpose, init) }.into_obj(cx).into()
+}
+
+#[defun]
+fn char_table_subtype(char_table: &CharTable) -> Object<'_> {
+    char_table.subtype()
+}
+
+/// Return the value in `char_table` for `range`. `range` is nil for the
+/// default value, a character, or a cons `(FROM . TO)` in which case the value
+/// of `FROM` is returned.
+#[defun]
+fn char_table_range<'ob>(char_table: &'ob CharTable, range: Object) -> Result<Object<'ob>> {
+    Ok(match range.untag() {
+        ObjectType::NIL => char_table.default(),
+        ObjectType::Int(chr) => char_table.get(chr),
+        ObjectType::Cons(cons) => match cons.car().untag() {
+            ObjectType::Int(from) => char_table.get(from),
+            _ => bail!("Invalid RANGE argument to `char-table-range': {range}"),
+        },
+        _ => bail!("Invalid RANGE argument to `char-table-range': {range}"),
+    })
+}
+
+/// Set the value in `char_table` for `range` to `value`. `range` is t for all
+/// characters, nil for the default value, a character, or a cons `(FROM .
+/// TO)` of an inclusive range of characters.
+#[defun]
+fn set_char_table_range<'ob>(
+    char_table: &CharTable,
+    range: Object,
+    value: Object<'ob>,
+    cx: &Context,
+) -> Result<Object<'ob>> {
+    match range.untag() {
+        ObjectType::TRUE => char_table.set_default(value, true)?,
+        ObjectType::NIL => char_table.set_default(value, false)?,
+        ObjectType::Int(chr) => char_table.set_range(chr, chr, value, cx)?,
+        ObjectType::Cons(cons) => match (cons.car().untag(), cons.cdr().untag()) {
+            (ObjectType::Int(from), ObjectType::Int(to)) => {
+                char_table.set_range(from, to, value, cx)?;
+            }
+            _ => bail!("Invalid RANGE argument to `set-char-table-range': {range}"),
+        },
+        _ => bail!("Invalid RANGE argument to `set-char-table-range': {range}"),
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::core::gc::{Context, RootSet};
+    use crate::core::object::{CharTableInner, IntoObject, NIL};
+    use crate::interpreter::assert_lisp;
+
+    #[test]
+    fn test_char_table() {
+        assert_lisp("(char-table-p (make-char-table 'test 'none))", "t");
+        assert_lisp("(char-table-subtype (make-char-table 'test 'none))", "test");
+        assert_lisp("(aref (make-char-table 'test 'none) ?a)", "none");
+        assert_lisp(
+            "(let ((ct (make-char-table 'test 'none)))
+               (set-char-table-range ct '(?a . ?z) 'lower)
+               (list (aref ct ?a) (aref ct ?m) (aref ct ?z) (aref ct ?A)))",
+            "(lower lower lower none)",
+        );
+        assert_lisp(
+            "(let ((ct (make-char-table 'test 'none)))
+               (set-char-table-range ct '(?a . ?z) 'lower)
+               (aset ct ?m 'em)
+               (list (aref ct ?l) (aref ct ?m) (char-table-range ct ?n)))",
+            "(lower em lower)",
+        );
+        assert_lisp(
+            "(let ((ct (make-char-table 'test 'none)))
+               (set-char-table-range ct nil 'default)
+               (list (aref ct ?a) (char-table-range ct nil)))",
+            "(default default)",
+        );
+        assert_lisp(
+            "(let ((ct (make-char-table 'test 'none)))
+               (aset ct ?a 1)
+               (set-char-table-range ct t 'all)
+               (list (aref ct ?a) (aref ct ?b)))",
+            "(all all)",
+        );
+        assert_lisp("(aref (make-char-table 'test) ?a)", "nil");
+        assert_lisp("(type-of (make-char-table 'test))", "char-table");
+        assert_lisp("(char-table-p [1])", "nil");
+        // older values that are still visible are kept
+        assert_lisp(
+            "(let ((ct (make-char-table 'test 'none)))
+               (set-char-table-range ct '(?a . ?z) 'lower)
+               (aset ct ?m 'em)
+               (aset ct ?m 'em2)
+               (set-char-table-range ct '(?l . ?n) 'mid)
+               (list (aref ct ?a) (aref ct ?l) (aref ct ?m) (aref ct ?o)))",
+            "(lower mid mid lower)",
+        );
+    }
+
+    #[test]
+    fn test_char_table_shadowed_ranges() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        let table = unsafe { CharTableInner::new(NIL, NIL) }.into_obj(cx).untag();
+        for i in 0..100 {
+            table.set_range('a' as i64, 'a' as i64, cx.add(i), cx).unwrap();
+        }
+        assert_eq!(table.range_count(), 1);
+        assert_eq!(table.get('a' as i64), 99);
+        table.set_range('b' as i64, 'c' as i64, cx.add(1), cx).unwrap();
+        table.set_range('c' as i64, 'd' as i64, cx.add(2), cx).unwrap();
+        assert_eq!(table.range_count(), 3);
+        // covers every earlier range
+        table.set_range('a' as i64, 'z' as i64, cx.add(3), cx).unwrap();
+        assert_eq!(table.range_count(), 1);
+        assert_eq!(table.get('b' as i64), 3);
+    }
+}
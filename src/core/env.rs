@@ -119,6 +119,10 @@ impl<'a> RootedEnv<'a> {
         self.vars.insert(var, value);
     }
 
+    pub(crate) fn binding_depth(&self) -> usize {
+        self.binding_stack.len()
+    }
+
     pub(crate) fn unbind(&mut self, count: u16, cx: &Context) {
         for _ in 0..count {
             match self.binding_stack.bind_mut(cx).pop() {
@@ -136,8 +140,8 @@ impl<'a> RootedEnv<'a> {
         // value
         if self.vars.get(var).is_none() {
             self.set_var(var, value)?;
-            var.make_special();
         }
+        var.make_special();
 
         // If this variable was unbound previously in the binding stack,
         // we will bind it to the new value
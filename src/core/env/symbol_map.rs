@@ -107,6 +107,26 @@ mod test {
         intern("foo", cx);
     }
 
+    #[test]
+    fn intern_static() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        sym::init_symbols();
+        // Special forms are dispatched by comparing against the static
+        // symbols, so interning their names must return the same symbol.
+        for (name, static_sym) in [
+            ("quote", sym::QUOTE),
+            ("function", sym::FUNCTION),
+            ("if", sym::IF),
+            ("let", sym::LET),
+            ("progn", sym::PROGN),
+            ("unwind-protect", sym::UNWIND_PROTECT),
+        ] {
+            assert_eq!(intern(name, cx), static_sym);
+            assert!(std::ptr::eq(intern(name, cx).get(), static_sym.get()));
+        }
+    }
+
     #[test]
     fn symbol_func() {
         let roots = &RootSet::default();
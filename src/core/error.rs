@@ -40,6 +40,8 @@ pub(crate) enum Type {
     Number,
     List,
     Buffer,
+    Marker,
+    CharTable,
 }
 
 /// Error provided if object was the wrong type
@@ -48,14 +50,20 @@ pub(crate) struct TypeError {
     expect: Type,
     actual: Type,
     print: String,
+    /// Zero-based position of the argument, if this came from a subr call.
+    arg: Option<usize>,
 }
 
 impl std::error::Error for TypeError {}
 
 impl Display for TypeError {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        let Self { expect, actual, print } = self;
-        write!(f, "expected {expect:?}, found {actual:?}: {print}")
+        let Self { expect, actual, print, arg } = self;
+        write!(f, "expected {expect:?}, found {actual:?}: {print}")?;
+        if let Some(arg) = arg {
+            write!(f, ", arg {arg}")?;
+        }
+        Ok(())
     }
 }
 
@@ -66,6 +74,15 @@ impl TypeError {
         T: Into<super::object::ObjectType<'ob>>,
     {
         let obj = obj.into();
-        Self { expect, actual: obj.get_type(), print: obj.to_string() }
+        Self { expect, actual: obj.get_type(), print: obj.to_string(), arg: None }
+    }
+}
+
+/// Record which argument of a subr call failed to convert. Errors other than
+/// [`TypeError`] are passed through unchanged.
+pub(crate) fn arg_type_error(err: impl Into<anyhow::Error>, idx: usize) -> anyhow::Error {
+    match err.into().downcast::<TypeError>() {
+        Ok(err) => TypeError { arg: Some(idx), ..err }.into(),
+        Err(err) => err,
     }
 }
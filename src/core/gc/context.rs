@@ -1,5 +1,5 @@
 use super::GcState;
-use super::Trace;
+use super::{Slot, Trace};
 use crate::core::object::GcString;
 use crate::core::object::LispHashTable;
 use crate::core::object::{Gc, IntoObject, Object, UninternedSymbolMap, WithLifetime};
@@ -14,8 +14,20 @@ use std::sync::atomic::AtomicBool;
 #[derive(Default, Debug)]
 pub(crate) struct RootSet {
     pub(super) roots: RefCell<Vec<*const dyn Trace>>,
+    // Objects pinned by [`Context::pin`]. Unlike `roots` these don't follow a
+    // stack discipline and live until they are explicitly unpinned. Freed
+    // entries are set to `None` so that the handles stay valid.
+    pinned: RefCell<Vec<Option<Slot<Object<'static>>>>>,
 }
 
+/// A handle to an object pinned with [`Context::pin`]. The object will survive
+/// garbage collection until the handle is passed to [`Context::unpin`]. This is
+/// for callers that need to hold onto an object across calls into the
+/// interpreter, where a stack root can't be used.
+#[cfg(test)]
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct PinHandle(usize);
+
 #[allow(dead_code)]
 // These types are only stored here so they can be dropped
 pub(in crate::core) enum DropStackElem {
@@ -175,6 +187,10 @@ impl<'ob, 'rt> Context<'rt> {
             }
         }
 
+        for slot in self.root_set.pinned.borrow().iter().flatten() {
+            slot.trace(&mut state);
+        }
+
         state.trace_stack();
 
         self.next_limit = (state.to_space.allocated_bytes() * Self::GC_GROWTH_FACTOR) / 10;
@@ -196,6 +212,42 @@ impl<'ob, 'rt> Context<'rt> {
     }
 }
 
+// Nothing in the crate pins objects yet, so this is only built for tests until
+// there is an embedding API to expose it through.
+#[cfg(test)]
+impl<'ob> Context<'_> {
+    /// Keep `obj` alive across garbage collections until it is unpinned.
+    pub(crate) fn pin(&self, obj: Object) -> PinHandle {
+        let slot = Some(Slot::new(unsafe { obj.with_lifetime() }));
+        let mut pinned = self.root_set.pinned.borrow_mut();
+        match pinned.iter().position(Option::is_none) {
+            Some(idx) => {
+                pinned[idx] = slot;
+                PinHandle(idx)
+            }
+            None => {
+                pinned.push(slot);
+                PinHandle(pinned.len() - 1)
+            }
+        }
+    }
+
+    /// Get the current value of a pinned object. The object may have moved
+    /// since it was pinned.
+    pub(crate) fn get_pinned(&'ob self, handle: &PinHandle) -> Object<'ob> {
+        let pinned = self.root_set.pinned.borrow();
+        let slot = pinned[handle.0].as_ref().expect("pinned object was already unpinned");
+        self.bind(**slot)
+    }
+
+    /// Release a pinned object, returning its current value. It will be
+    /// collected once nothing else references it.
+    pub(crate) fn unpin(&'ob self, handle: PinHandle) -> Object<'ob> {
+        let slot = self.root_set.pinned.borrow_mut()[handle.0].take();
+        self.bind(*slot.expect("pinned object was already unpinned"))
+    }
+}
+
 impl<'rt> Deref for Context<'rt> {
     type Target = Block<false>;
 
@@ -288,4 +340,22 @@ mod test {
         assert_eq!(**float, 1.5);
         assert_eq!(int, 1);
     }
+
+    #[test]
+    fn test_pin() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        let handle = cx.pin(cx.add("pinned"));
+        cx.garbage_collect(true);
+        let pinned_bytes = cx.block.objects.allocated_bytes();
+        assert_eq!(cx.get_pinned(&handle), "pinned");
+        // an unpinned object does not survive collection
+        _ = cx.add("unpinned");
+        cx.garbage_collect(true);
+        assert_eq!(cx.block.objects.allocated_bytes(), pinned_bytes);
+        assert_eq!(cx.get_pinned(&handle), "pinned");
+        assert_eq!(cx.unpin(handle), "pinned");
+        cx.garbage_collect(true);
+        assert_eq!(cx.block.objects.allocated_bytes(), 0);
+    }
 }
@@ -11,10 +11,12 @@
 
 mod buffer;
 mod cell;
+mod chartable;
 mod convert;
 mod float;
 mod func;
 mod hashtable;
+mod marker;
 mod string;
 mod symbol;
 mod tagged;
@@ -22,10 +24,12 @@ mod vector;
 
 pub(crate) use buffer::*;
 pub(super) use cell::*;
+pub(crate) use chartable::*;
 pub(crate) use convert::*;
 pub(crate) use float::*;
 pub(crate) use func::*;
 pub(crate) use hashtable::*;
+pub(crate) use marker::*;
 pub(crate) use string::*;
 pub(crate) use symbol::*;
 pub(crate) use tagged::*;
@@ -0,0 +1,173 @@
+use super::{CloneIn, Gc, IntoObject, ObjCell, Object, ObjectType, NIL};
+use crate::{
+    core::{
+        cons::Cons,
+        gc::{Block, GcHeap, GcState, Trace},
+    },
+    NewtypeMarkable,
+};
+use anyhow::{bail, Result};
+use macro_attr_2018::macro_attr;
+use newtype_derive_2018::*;
+use rune_macros::Trace;
+use std::fmt::{self, Debug, Display};
+
+#[derive(PartialEq, Eq)]
+pub(crate) struct CharTableInner {
+    is_const: bool,
+    subtype: ObjCell,
+    default: ObjCell,
+    // A list of `((FROM . TO) . VALUE)' entries, newest first. Since newer
+    // entries shadow older ones we don't need to split existing ranges when a
+    // range is set. Entries that are completely covered by a newer one are
+    // removed.
+    ranges: ObjCell,
+}
+
+macro_attr! {
+    /// A table mapping characters to values. Characters that were never set
+    /// map to the default value of the table.
+    #[derive(PartialEq, Eq, NewtypeDeref!, NewtypeMarkable!, Trace)]
+    pub(crate) struct CharTable(GcHeap<CharTableInner>);
+}
+
+impl CharTable {
+    pub(in crate::core) fn new(inner: CharTableInner, constant: bool) -> Self {
+        let inner = CharTableInner { is_const: constant, ..inner };
+        CharTable(GcHeap::new(inner, constant))
+    }
+}
+
+impl CharTableInner {
+    // SAFETY: This type must immediately be put into the GC heap, because
+    // holding it past garbage collections is unsafe.
+    pub(crate) unsafe fn new(subtype: Object, default: Object) -> Self {
+        Self {
+            is_const: false,
+            subtype: ObjCell::new(subtype),
+            default: ObjCell::new(default),
+            ranges: ObjCell::new(NIL),
+        }
+    }
+
+    pub(crate) fn subtype(&self) -> Object<'_> {
+        self.subtype.get()
+    }
+
+    pub(crate) fn default(&self) -> Object<'_> {
+        self.default.get()
+    }
+
+    /// Split a `((FROM . TO) . VALUE)' entry into its parts.
+    fn entry(entry: Object) -> (i64, i64, Object) {
+        let ObjectType::Cons(entry) = entry.untag() else { unreachable!() };
+        let ObjectType::Cons(range) = entry.car().untag() else { unreachable!() };
+        let (ObjectType::Int(from), ObjectType::Int(to)) =
+            (range.car().untag(), range.cdr().untag())
+        else {
+            unreachable!()
+        };
+        (from, to, entry.cdr())
+    }
+
+    /// Return the value of `chr`, falling back to the default value of the
+    /// table.
+    pub(crate) fn get(&self, chr: i64) -> Object<'_> {
+        let mut entries = self.ranges.get();
+        while let ObjectType::Cons(cons) = entries.untag() {
+            let (from, to, value) = Self::entry(cons.car());
+            if (from..=to).contains(&chr) {
+                return value;
+            }
+            entries = cons.cdr();
+        }
+        self.default()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn range_count(&self) -> usize {
+        self.ranges.get().as_list().unwrap().count()
+    }
+
+    fn check_mut(&self) -> Result<()> {
+        if self.is_const {
+            bail!("Attempt to mutate constant char-table");
+        }
+        Ok(())
+    }
+
+    /// Set the characters from `from` to `to` inclusive to `value`.
+    pub(crate) fn set_range<const C: bool>(
+        &self,
+        from: i64,
+        to: i64,
+        value: Object,
+        block: &Block<C>,
+    ) -> Result<()> {
+        self.check_mut()?;
+        // Keep only the entries that are still visible through the new range
+        let mut kept = Vec::new();
+        let mut entries = self.ranges.get();
+        while let ObjectType::Cons(cons) = entries.untag() {
+            let (entry_from, entry_to, _) = Self::entry(cons.car());
+            if entry_from < from || to < entry_to {
+                kept.push(cons.car());
+            }
+            entries = cons.cdr();
+        }
+        let mut ranges = NIL;
+        for entry in kept.into_iter().rev() {
+            ranges = Cons::new(entry, ranges, block).into();
+        }
+        let entry = Cons::new(Cons::new(from, to, block), value, block);
+        let ranges = Cons::new(entry, ranges, block);
+        unsafe { self.ranges.as_mut().set(ranges.into()) };
+        Ok(())
+    }
+
+    /// Set the default value. If `clear` is true, then all characters will map
+    /// to the default value.
+    pub(crate) fn set_default(&self, value: Object, clear: bool) -> Result<()> {
+        self.check_mut()?;
+        unsafe {
+            self.default.as_mut().set(value);
+            if clear {
+                self.ranges.as_mut().set(NIL);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Trace for CharTableInner {
+    fn trace(&self, state: &mut GcState) {
+        self.subtype.trace(state);
+        self.default.trace(state);
+        self.ranges.trace(state);
+    }
+}
+
+impl<'new> CloneIn<'new, &'new Self> for CharTable {
+    fn clone_in<const C: bool>(&self, bk: &'new Block<C>) -> Gc<&'new Self> {
+        let subtype = self.subtype().clone_in(bk);
+        let default = self.default().clone_in(bk);
+        let ranges = self.ranges.get().clone_in(bk);
+        let table = CharTableInner {
+            ranges: unsafe { ObjCell::new(ranges) },
+            ..unsafe { CharTableInner::new(subtype, default) }
+        };
+        table.into_obj(bk)
+    }
+}
+
+impl Display for CharTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#<char-table {}>", self.subtype())
+    }
+}
+
+impl Debug for CharTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
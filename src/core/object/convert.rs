@@ -4,7 +4,7 @@
 
 use super::{
     super::error::{ArgError, Type, TypeError},
-    ByteString, LispHashTable, LispString, LispVec, OptionalFlag, NIL, TRUE,
+    ByteString, CharTable, LispHashTable, LispString, LispVec, Marker, OptionalFlag, NIL, TRUE,
 };
 use super::{Gc, LispFloat, Object, ObjectType, Symbol};
 use anyhow::Context;
@@ -125,6 +125,8 @@ impl<'ob> From<bool> for Object<'ob> {
 define_unbox!(Int, i64);
 define_unbox!(Float, &'ob LispFloat);
 define_unbox!(HashTable, &'ob LispHashTable);
+define_unbox!(Marker, &'ob Marker);
+define_unbox!(CharTable, &'ob CharTable);
 define_unbox!(String, &'ob LispString);
 define_unbox!(ByteString, String, &'ob ByteString);
 define_unbox!(Vec, &'ob LispVec);
@@ -36,7 +36,13 @@ impl<'new> CloneIn<'new, &'new LispFloat> for LispFloat {
 impl Display for LispFloat {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let float = **self;
-        if float.fract() == 0.0_f64 {
+        // Emacs spells the special floats so that they read back as floats
+        let sign = if float.is_sign_negative() { "-" } else { "" };
+        if float.is_nan() {
+            write!(f, "{sign}0.0e+NaN")
+        } else if float.is_infinite() {
+            write!(f, "{sign}1.0e+INF")
+        } else if float.fract() == 0.0_f64 {
             write!(f, "{float:.1}")
         } else {
             write!(f, "{float}")
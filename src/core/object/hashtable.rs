@@ -2,7 +2,7 @@
 //! need it to support being both thread local and global. Second we need
 //! iterate and mutate at the same time. Third we need to be able to clean up
 //! the heap allocation when it is garbage collected.
-use super::{CloneIn, Gc, IntoObject, ObjCell, Object, WithLifetime};
+use super::{CloneIn, Gc, IntoObject, ObjCell, Object, ObjectType, WithLifetime};
 use crate::core::env::interned_symbols;
 use crate::core::gc::{Block, GcHeap, GcState, Trace};
 use crate::NewtypeMarkable;
@@ -12,10 +12,163 @@ use rune_core::hashmap::{HashSet, IndexMap};
 use rune_macros::Trace;
 use std::cell::RefCell;
 use std::fmt::{self, Debug, Display, Write};
+use std::hash::{Hash, Hasher};
 use std::ptr::NonNull;
 use std::sync::Mutex;
 
-pub(crate) type HashTable<'ob> = IndexMap<Object<'ob>, Object<'ob>>;
+/// The function used to compare keys of a hash table, as given by the `:test`
+/// argument of `make-hash-table'.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) enum HashTest {
+    Eq,
+    #[default]
+    Eql,
+    Equal,
+}
+
+/// How deep into nested conses and vectors an `equal' key is hashed. Anything
+/// below this depth does not contribute to the hash.
+const HASH_DEPTH: usize = 3;
+
+/// A key in a [`HashTable`]. Hashing and equality are dispatched on the test
+/// of the table, so that `eq' tables only look at object identity while
+/// `equal' tables look at the contents.
+#[derive(Copy, Clone)]
+struct HashKey<'ob> {
+    obj: Object<'ob>,
+    test: HashTest,
+}
+
+impl PartialEq for HashKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match self.test {
+            HashTest::Eq => self.obj.ptr_eq(other.obj),
+            HashTest::Eql => match (self.obj.untag(), other.obj.untag()) {
+                (ObjectType::Float(x), ObjectType::Float(y)) => x.to_bits() == y.to_bits(),
+                _ => self.obj.ptr_eq(other.obj),
+            },
+            HashTest::Equal => self.obj == other.obj,
+        }
+    }
+}
+
+impl Eq for HashKey<'_> {}
+
+impl Hash for HashKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self.test {
+            HashTest::Eq => self.obj.hash(state),
+            HashTest::Eql => match self.obj.untag() {
+                ObjectType::Float(x) => x.to_bits().hash(state),
+                _ => self.obj.hash(state),
+            },
+            HashTest::Equal => hash_equal(self.obj, HASH_DEPTH, state),
+        }
+    }
+}
+
+/// Hash `obj` such that any two objects that are `equal' have the same hash.
+fn hash_equal<H: Hasher>(obj: Object, depth: usize, state: &mut H) {
+    if depth == 0 {
+        return;
+    }
+    match obj.untag() {
+        ObjectType::String(x) => x.as_bytes().hash(state),
+        ObjectType::ByteString(x) => x[..].hash(state),
+        // 0.0 and -0.0 are `equal'
+        ObjectType::Float(x) => (**x + 0.0).to_bits().hash(state),
+        ObjectType::Cons(x) => {
+            hash_equal(x.car(), depth - 1, state);
+            hash_equal(x.cdr(), depth - 1, state);
+        }
+        ObjectType::Vec(x) => x.iter().for_each(|x| hash_equal(x.get(), depth - 1, state)),
+        ObjectType::Record(x) => x.iter().for_each(|x| hash_equal(x.get(), depth - 1, state)),
+        // These are compared by their contents, which we don't hash
+        ObjectType::ByteFn(_)
+        | ObjectType::SubrFn(_)
+        | ObjectType::Marker(_)
+        | ObjectType::CharTable(_) => {
+            std::mem::discriminant(&obj.untag()).hash(state);
+        }
+        _ => obj.hash(state),
+    }
+}
+
+/// The fraction of the capacity of a [`HashTable`] that can be filled before
+/// it grows. The reported capacity already accounts for the load factor of the
+/// underlying map, so the table only grows once it is full.
+pub(crate) const HASH_TABLE_REHASH_THRESHOLD: f64 = 1.0;
+
+/// The factor by which the capacity of a [`HashTable`] grows when it is full.
+pub(crate) const HASH_TABLE_REHASH_SIZE: f64 = 2.0;
+
+/// A map between lisp objects. Keys are compared using the [`HashTest`] of the
+/// table.
+#[derive(Default, Clone)]
+pub(crate) struct HashTable<'ob> {
+    test: HashTest,
+    map: IndexMap<HashKey<'ob>, Object<'ob>>,
+}
+
+impl<'ob> HashTable<'ob> {
+    pub(crate) fn new(test: HashTest) -> Self {
+        Self { test, map: IndexMap::default() }
+    }
+
+    /// Create a table with room for at least `size` entries before it needs
+    /// to grow. Fails instead of aborting if that much memory is not
+    /// available.
+    pub(crate) fn with_capacity(test: HashTest, size: usize) -> anyhow::Result<Self> {
+        let mut map = IndexMap::default();
+        if let Err(e) = map.try_reserve(size) {
+            anyhow::bail!("Hash table size {size} is too large: {e}");
+        }
+        Ok(Self { test, map })
+    }
+
+    pub(crate) fn test(&self) -> HashTest {
+        self.test
+    }
+
+    fn key(&self, obj: Object<'ob>) -> HashKey<'ob> {
+        HashKey { obj, test: self.test }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// The number of entries the table can hold without growing.
+    pub(crate) fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    pub(crate) fn get(&self, key: &Object<'ob>) -> Option<&Object<'ob>> {
+        self.map.get(&self.key(*key))
+    }
+
+    pub(crate) fn get_index(&self, index: usize) -> Option<(Object<'ob>, Object<'ob>)> {
+        self.map.get_index(index).map(|(k, v)| (k.obj, *v))
+    }
+
+    pub(crate) fn get_index_of(&self, key: &Object<'ob>) -> Option<usize> {
+        self.map.get_index_of(&self.key(*key))
+    }
+
+    pub(crate) fn insert(&mut self, key: Object<'ob>, value: Object<'ob>) -> Option<Object<'ob>> {
+        let key = self.key(key);
+        self.map.insert(key, value)
+    }
+
+    pub(crate) fn shift_remove(&mut self, key: &Object<'ob>) -> Option<Object<'ob>> {
+        let key = self.key(*key);
+        self.map.shift_remove(&key)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Object<'ob>, Object<'ob>)> + '_ {
+        self.map.iter().map(|(k, v)| (k.obj, *v))
+    }
+}
 
 macro_attr! {
     #[derive(PartialEq, Eq, NewtypeDebug!, NewtypeDisplay!, NewtypeDeref!, NewtypeMarkable!, Trace)]
@@ -79,15 +232,30 @@ impl<'a> HashTableCore<'a> {
         self.with(|x| x.len())
     }
 
+    pub(crate) fn capacity(&self) -> usize {
+        self.with(|x| x.capacity())
+    }
+
+    /// A new table with the same test and entries as this one.
+    pub(crate) fn copy(&self) -> HashTable<'_> {
+        self.with(|x| x.clone())
+    }
+
+    pub(crate) fn test(&self) -> HashTest {
+        self.with(|x| x.test())
+    }
+
     pub(crate) fn get(&self, key: Object) -> Option<Object<'_>> {
+        let key = unsafe { key.with_lifetime() };
         self.with(|x| x.get(&key).copied())
     }
 
     pub(crate) fn get_index(&self, index: usize) -> Option<(Object, Object)> {
-        self.with(|x| x.get_index(index).map(|(k, v)| (*k, *v)))
+        self.with(|x| x.get_index(index))
     }
 
     pub(crate) fn get_index_of(&self, key: Object) -> Option<usize> {
+        let key = unsafe { key.with_lifetime() };
         self.with(|x| x.get_index_of(&key))
     }
 
@@ -135,17 +303,13 @@ impl Trace for HashTableCore<'_> {
         let HashTableType::Local(table) = &self.0 else {
             panic!("Global hash table should not be traced")
         };
-        let table = &mut table.borrow_mut().inner;
+        let table = &mut table.borrow_mut().inner.map;
         // ObjCell are updated in place when traced, so casting to ObjCell will
         // allow all the objects to be updated.
-        let table = unsafe {
-            std::mem::transmute::<&mut IndexMap<Object, Object>, &mut IndexMap<ObjCell, ObjCell>>(
-                table,
-            )
-        };
+        let as_cell = |obj: &mut Object| unsafe { &*std::ptr::from_mut(obj).cast::<ObjCell>() };
         table.rehash_keys(|key, val| {
-            key.trace(state);
-            val.trace(state);
+            as_cell(&mut key.obj).trace(state);
+            as_cell(val).trace(state);
         });
     }
 }
@@ -171,9 +335,9 @@ impl Display for HashTableCore<'_> {
 
 impl<'new> CloneIn<'new, &'new Self> for LispHashTable {
     fn clone_in<const C: bool>(&self, bk: &'new Block<C>) -> Gc<&'new Self> {
-        let mut table = HashTable::default();
+        let mut table = HashTable::new(self.test());
         self.with(|x| {
-            for (key, value) in x {
+            for (key, value) in x.iter() {
                 let new_key = key.clone_in(bk);
                 let new_value = value.clone_in(bk);
                 table.insert(new_key, new_value);
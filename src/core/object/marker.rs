@@ -0,0 +1,68 @@
+use super::{CloneIn, IntoObject};
+use crate::core::gc::{Block, GcHeap, GcState, Trace};
+use crate::NewtypeMarkable;
+use macro_attr_2018::macro_attr;
+use newtype_derive_2018::*;
+use rune_macros::Trace;
+use std::cell::Cell;
+use std::fmt::{self, Debug, Display};
+
+/// The contents of a [`Marker`]. A marker that does not point anywhere has no
+/// position.
+#[derive(PartialEq, Eq, Default)]
+pub(crate) struct MarkerInner {
+    position: Cell<Option<usize>>,
+}
+
+macro_attr! {
+    /// A position that can be updated in place. We don't have buffers that
+    /// markers can point into yet, so this is just the position, but it is
+    /// enough for code that uses markers as mutable integers.
+    #[derive(PartialEq, Eq, NewtypeDeref!, NewtypeMarkable!, Trace)]
+    pub(crate) struct Marker(GcHeap<MarkerInner>);
+}
+
+impl Marker {
+    pub(in crate::core) fn new(inner: MarkerInner, constant: bool) -> Self {
+        Marker(GcHeap::new(inner, constant))
+    }
+}
+
+impl MarkerInner {
+    pub(crate) fn new(position: Option<usize>) -> Self {
+        Self { position: Cell::new(position) }
+    }
+
+    pub(crate) fn position(&self) -> Option<usize> {
+        self.position.get()
+    }
+
+    pub(crate) fn set_position(&self, position: Option<usize>) {
+        self.position.set(position);
+    }
+}
+
+impl Trace for MarkerInner {
+    fn trace(&self, _: &mut GcState) {}
+}
+
+impl<'new> CloneIn<'new, &'new Marker> for Marker {
+    fn clone_in<const C: bool>(&self, bk: &'new Block<C>) -> super::Gc<&'new Self> {
+        MarkerInner::new(self.position()).into_obj(bk)
+    }
+}
+
+impl Display for Marker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position() {
+            Some(position) => write!(f, "#<marker at {position}>"),
+            None => write!(f, "#<marker in no buffer>"),
+        }
+    }
+}
+
+impl Debug for Marker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
@@ -5,7 +5,8 @@ use super::{
         error::{Type, TypeError},
         gc::Block,
     },
-    ByteFnPrototype, ByteString, GcString, LispBuffer,
+    ByteFnPrototype, ByteString, CharTable, CharTableInner, GcString, LispBuffer, Marker,
+    MarkerInner,
 };
 use super::{
     ByteFn, HashTable, LispFloat, LispHashTable, LispString, LispVec, Record, RecordBuilder,
@@ -289,6 +290,8 @@ object_trait_impls!(LispVec);
 object_trait_impls!(Record);
 object_trait_impls!(LispHashTable);
 object_trait_impls!(LispBuffer);
+object_trait_impls!(Marker);
+object_trait_impls!(CharTable);
 
 /// Trait for types that can be managed by the GC. This trait is implemented for
 /// as many types as possible, even for types that are already Gc managed, Like
@@ -373,6 +376,24 @@ impl IntoObject for Cons {
     }
 }
 
+impl IntoObject for MarkerInner {
+    type Out<'ob> = &'ob Marker;
+
+    fn into_obj<const C: bool>(self, block: &Block<C>) -> Gc<Self::Out<'_>> {
+        let ptr = block.objects.alloc(Marker::new(self, C));
+        unsafe { Self::Out::tag_ptr(ptr) }
+    }
+}
+
+impl IntoObject for CharTableInner {
+    type Out<'ob> = &'ob CharTable;
+
+    fn into_obj<const C: bool>(self, block: &Block<C>) -> Gc<Self::Out<'_>> {
+        let ptr = block.objects.alloc(CharTable::new(self, C));
+        unsafe { Self::Out::tag_ptr(ptr) }
+    }
+}
+
 impl IntoObject for ByteFnPrototype {
     type Out<'ob> = &'ob ByteFn;
 
@@ -519,6 +540,8 @@ mod private {
         SubrFn,
         ByteFn,
         Buffer,
+        Marker,
+        CharTable,
     }
 
     /// Trait for tagged pointers. Anything that can be stored and passed around
@@ -625,6 +648,8 @@ impl<'a> TaggedPtr for ObjectType<'a> {
                 Tag::Record => ObjectType::Record(<&Record>::from_obj_ptr(ptr)),
                 Tag::HashTable => ObjectType::HashTable(<&LispHashTable>::from_obj_ptr(ptr)),
                 Tag::Buffer => ObjectType::Buffer(<&LispBuffer>::from_obj_ptr(ptr)),
+                Tag::Marker => ObjectType::Marker(<&Marker>::from_obj_ptr(ptr)),
+                Tag::CharTable => ObjectType::CharTable(<&CharTable>::from_obj_ptr(ptr)),
             }
         }
     }
@@ -643,6 +668,8 @@ impl<'a> TaggedPtr for ObjectType<'a> {
             ObjectType::ByteFn(x) => TaggedPtr::tag(x).into(),
             ObjectType::SubrFn(x) => TaggedPtr::tag(x).into(),
             ObjectType::Buffer(x) => TaggedPtr::tag(x).into(),
+            ObjectType::Marker(x) => TaggedPtr::tag(x).into(),
+            ObjectType::CharTable(x) => TaggedPtr::tag(x).into(),
         }
     }
 }
@@ -885,6 +912,30 @@ impl TaggedPtr for &LispHashTable {
     }
 }
 
+impl TaggedPtr for &Marker {
+    type Ptr = Marker;
+    const TAG: Tag = Tag::Marker;
+    unsafe fn from_obj_ptr(ptr: *const u8) -> Self {
+        &*ptr.cast::<Self::Ptr>()
+    }
+
+    fn get_ptr(self) -> *const Self::Ptr {
+        self as *const Self::Ptr
+    }
+}
+
+impl TaggedPtr for &CharTable {
+    type Ptr = CharTable;
+    const TAG: Tag = Tag::CharTable;
+    unsafe fn from_obj_ptr(ptr: *const u8) -> Self {
+        &*ptr.cast::<Self::Ptr>()
+    }
+
+    fn get_ptr(self) -> *const Self::Ptr {
+        self as *const Self::Ptr
+    }
+}
+
 impl TaggedPtr for &LispBuffer {
     type Ptr = LispBuffer;
     const TAG: Tag = Tag::Buffer;
@@ -1009,6 +1060,8 @@ pub(crate) enum ObjectType<'ob> {
     ByteFn(&'ob ByteFn) = Tag::ByteFn as u8,
     SubrFn(&'static SubrFn) = Tag::SubrFn as u8,
     Buffer(&'static LispBuffer) = Tag::Buffer as u8,
+    Marker(&'ob Marker) = Tag::Marker as u8,
+    CharTable(&'ob CharTable) = Tag::CharTable as u8,
 }
 
 /// The Object defintion that contains all other possible lisp objects. This
@@ -1029,7 +1082,9 @@ cast_gc!(ObjectType<'ob> => NumberType<'ob>,
          &'ob ByteString,
          &'ob ByteFn,
          &'ob SubrFn,
-         &'ob LispBuffer
+         &'ob LispBuffer,
+         &'ob Marker,
+         &'ob CharTable
 );
 
 impl ObjectType<'_> {
@@ -1049,6 +1104,8 @@ impl ObjectType<'_> {
             ObjectType::ByteString(_) => Type::String,
             ObjectType::ByteFn(_) | ObjectType::SubrFn(_) => Type::Func,
             ObjectType::Buffer(_) => Type::Buffer,
+            ObjectType::Marker(_) => Type::Marker,
+            ObjectType::CharTable(_) => Type::CharTable,
         }
     }
 }
@@ -1265,6 +1322,28 @@ impl<'ob> TryFrom<Object<'ob>> for Gc<&'ob ByteString> {
     }
 }
 
+impl<'ob> TryFrom<Object<'ob>> for Gc<&'ob Marker> {
+    type Error = TypeError;
+
+    fn try_from(value: Object<'ob>) -> Result<Self, Self::Error> {
+        match value.get_tag() {
+            Tag::Marker => unsafe { Ok(cast_gc(value)) },
+            _ => Err(TypeError::new(Type::Marker, value)),
+        }
+    }
+}
+
+impl<'ob> TryFrom<Object<'ob>> for Gc<&'ob CharTable> {
+    type Error = TypeError;
+
+    fn try_from(value: Object<'ob>) -> Result<Self, Self::Error> {
+        match value.get_tag() {
+            Tag::CharTable => unsafe { Ok(cast_gc(value)) },
+            _ => Err(TypeError::new(Type::CharTable, value)),
+        }
+    }
+}
+
 impl<'ob> TryFrom<Object<'ob>> for Gc<&'ob LispHashTable> {
     type Error = TypeError;
 
@@ -1333,6 +1412,8 @@ where
             ObjectType::Record(x) => x.clone_in(bk).into(),
             ObjectType::HashTable(x) => x.clone_in(bk).into(),
             ObjectType::Buffer(x) => x.clone_in(bk).into(),
+            ObjectType::Marker(x) => x.clone_in(bk).into(),
+            ObjectType::CharTable(x) => x.clone_in(bk).into(),
         };
         let Ok(x) = Gc::<U>::try_from(obj) else { unreachable!() };
         x
@@ -1353,6 +1434,8 @@ impl<T> Trace for Gc<T> {
             ObjectType::Symbol(x) => x.trace(state),
             ObjectType::ByteFn(x) => x.trace(state),
             ObjectType::Buffer(x) => x.trace(state),
+            ObjectType::Marker(x) => x.trace(state),
+            ObjectType::CharTable(x) => x.trace(state),
         }
     }
 }
@@ -1384,6 +1467,8 @@ impl Markable for Object<'_> {
             ObjectType::ByteString(x) => cast_pair(x.move_value(to_space)?),
             ObjectType::ByteFn(x) => cast_pair(x.move_value(to_space)?),
             ObjectType::Buffer(x) => cast_pair(x.move_value(to_space)?),
+            ObjectType::Marker(x) => cast_pair(x.move_value(to_space)?),
+            ObjectType::CharTable(x) => cast_pair(x.move_value(to_space)?),
             ObjectType::Symbol(x) => {
                 // Need to handle specially because a symbol is not a pointer,
                 // but rather an offset
@@ -1583,6 +1668,8 @@ impl ObjectType<'_> {
             ObjectType::SubrFn(x) => D::fmt(x, f),
             ObjectType::Float(x) => D::fmt(x, f),
             ObjectType::Buffer(x) => D::fmt(x, f),
+            ObjectType::Marker(x) => D::fmt(x, f),
+            ObjectType::CharTable(x) => D::fmt(x, f),
         }
     }
 }
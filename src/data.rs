@@ -3,11 +3,18 @@ use crate::core::{
     cons::Cons,
     env::{interned_symbols, sym, Env},
     error::{Type, TypeError},
-    gc::{Context, Rt},
-    object::{List, ListType, Number, Object, ObjectType, SubrFn, Symbol, WithLifetime, NIL},
+    gc::{Context, Rt, Rto},
+    object::{
+        FunctionType, Gc, List, ListType, Number, NumberType, Object, ObjectType, SubrFn, Symbol,
+        WithLifetime, NIL,
+    },
 };
-use anyhow::{anyhow, Result};
+use crate::rooted_iter;
+use anyhow::{anyhow, bail, ensure, Result};
+use fallible_iterator::FallibleIterator;
+use fallible_streaming_iterator::FallibleStreamingIterator;
 use rune_core::hashmap::HashSet;
+use rune_core::macros::call;
 use rune_macros::defun;
 use std::sync::Mutex;
 use std::sync::OnceLock;
@@ -36,13 +43,21 @@ pub(crate) fn fset<'ob>(symbol: Symbol<'ob>, definition: Object) -> Result<Symbo
     Ok(symbol)
 }
 
+defsym!(FUNCTION_DOCUMENTATION);
+
 #[defun]
 pub(crate) fn defalias<'ob>(
     symbol: Symbol<'ob>,
     definition: Object,
-    _docstring: Option<&str>,
+    docstring: Option<&str>,
+    env: &mut Rt<Env>,
+    cx: &Context,
 ) -> Result<Symbol<'ob>> {
-    fset(symbol, definition)
+    fset(symbol, definition)?;
+    if let Some(docstring) = docstring {
+        env.set_prop(symbol, sym::FUNCTION_DOCUMENTATION, cx.add(docstring));
+    }
+    Ok(symbol)
 }
 
 #[defun]
@@ -90,7 +105,7 @@ pub(crate) fn local_variable_if_set_p(_sym: Symbol) -> bool {
 
 #[defun]
 pub(crate) fn default_value<'ob>(
-    symbol: Symbol,
+    symbol: Symbol<'ob>,
     env: &Rt<Env>,
     cx: &'ob Context,
 ) -> Result<Object<'ob>> {
@@ -98,6 +113,9 @@ pub(crate) fn default_value<'ob>(
     symbol_value(symbol, env, cx).ok_or_else(|| anyhow!("Void variable: {symbol}"))
 }
 
+/// Return the function cell of `symbol` without following aliases. An unset
+/// cell is nil rather than a void-function error. Emacs has done this since
+/// 24.4, and code such as byte-opt.el relies on it to test for a definition.
 #[defun]
 pub(crate) fn symbol_function<'ob>(symbol: Symbol, cx: &'ob Context) -> Object<'ob> {
     match symbol.func(cx) {
@@ -108,10 +126,14 @@ pub(crate) fn symbol_function<'ob>(symbol: Symbol, cx: &'ob Context) -> Object<'
 
 #[defun]
 pub(crate) fn symbol_value<'ob>(
-    symbol: Symbol,
+    symbol: Symbol<'ob>,
     env: &Rt<Env>,
     cx: &'ob Context,
 ) -> Option<Object<'ob>> {
+    // Keywords, nil and t evaluate to themselves
+    if symbol.is_const() {
+        return Some(symbol.into());
+    }
     env.vars.get(symbol).map(|x| x.bind(cx))
 }
 
@@ -138,7 +160,7 @@ pub(crate) fn fmakunbound(symbol: Symbol) -> Symbol {
 
 #[defun]
 pub(crate) fn boundp(symbol: Symbol, env: &Rt<Env>) -> bool {
-    env.vars.get(symbol).is_some()
+    symbol.is_const() || env.vars.get(symbol).is_some()
 }
 
 #[defun]
@@ -168,11 +190,24 @@ pub(crate) fn symbolp(object: Object) -> bool {
 }
 
 #[defun]
-pub(crate) fn functionp(object: Object) -> bool {
+pub(crate) fn functionp(object: Object, cx: &Context) -> bool {
     match object.untag() {
         ObjectType::ByteFn(_) | ObjectType::SubrFn(_) => true,
-        ObjectType::Cons(cons) => cons.car() == sym::CLOSURE,
-        ObjectType::Symbol(sym) => sym.has_func(),
+        ObjectType::Cons(cons) => {
+            matches!(cons.car().untag(), ObjectType::Symbol(sym::CLOSURE | sym::LAMBDA))
+        }
+        ObjectType::Symbol(sym) => match sym.follow_indirect(cx) {
+            Some(func) => match func.untag() {
+                // macros are not functions, and neither are autoloaded macros
+                FunctionType::Cons(cons) if cons.car() == sym::MACRO => false,
+                FunctionType::Cons(cons) if cons.car() == sym::AUTOLOAD => {
+                    let kind = cons.elements().nth(4).and_then(|x| x.ok());
+                    !matches!(kind, Some(kind) if kind == sym::MACRO)
+                }
+                _ => functionp(func.into(), cx),
+            },
+            None => false,
+        },
         _ => false,
     }
 }
@@ -193,9 +228,13 @@ pub(crate) fn numberp(object: Object) -> bool {
 }
 
 #[defun]
-pub(crate) fn markerp(_: Object) -> bool {
-    // TODO: implement
-    false
+pub(crate) fn markerp(object: Object) -> bool {
+    matches!(object.untag(), ObjectType::Marker(_))
+}
+
+#[defun]
+pub(crate) fn char_table_p(object: Object) -> bool {
+    matches!(object.untag(), ObjectType::CharTable(_))
 }
 
 #[defun]
@@ -258,17 +297,62 @@ pub(crate) fn multibyte_string_p(object: Object) -> bool {
 }
 
 #[defun]
-fn string_to_number<'ob>(string: &str, base: Option<i64>, cx: &'ob Context) -> Number<'ob> {
-    // TODO: Handle trailing characters, which should be ignored
+fn number_to_string(number: Number) -> String {
+    match number.untag() {
+        NumberType::Int(x) => x.to_string(),
+        NumberType::Float(x) => x.to_string(),
+    }
+}
+
+#[defun]
+fn string_to_number<'ob>(string: &str, base: Option<i64>, cx: &'ob Context) -> Result<Number<'ob>> {
     let base = base.unwrap_or(10);
-    let string = string.trim();
-    match i64::from_str_radix(string, base as u32) {
-        Ok(x) => x.into(),
-        Err(_) => match string.parse::<f64>() {
-            Ok(x) => cx.add_as(x),
-            Err(_) => 0.into(),
-        },
+    ensure!((2..=16).contains(&base), "Args out of range: {base}");
+    let string = string.trim_start_matches([' ', '\t']);
+    let (negative, rest) = match string.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, string.strip_prefix('+').unwrap_or(string)),
+    };
+    let int_len = rest.find(|c: char| !c.is_digit(base as u32)).unwrap_or(rest.len());
+    // Only decimal numbers can be parsed as floats
+    if base == 10 {
+        if let Some(float_len) = float_prefix_len(rest, int_len) {
+            let float: f64 = rest[..float_len].parse().unwrap_or_default();
+            return Ok(cx.add_as(if negative { -float } else { float }));
+        }
+    }
+    // Out of range integers are truncated to 0 like unparsable input
+    let int = i64::from_str_radix(&rest[..int_len], base as u32).unwrap_or_default();
+    Ok((if negative { -int } else { int }).into())
+}
+
+/// Return the length of the float at the start of `string` if it has a
+/// fractional part or an exponent. `int_len` is the number of leading decimal
+/// digits.
+fn float_prefix_len(string: &str, int_len: usize) -> Option<usize> {
+    let digits_at = |start: usize| {
+        string[start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(string.len() - start)
+    };
+    let mut len = int_len;
+    let mut is_float = false;
+    if string[len..].starts_with('.') {
+        let frac_len = digits_at(len + 1);
+        if frac_len > 0 {
+            len += 1 + frac_len;
+            is_float = true;
+        }
+    }
+    if len > 0 && string[len..].starts_with(['e', 'E']) {
+        let sign_len = usize::from(string[len + 1..].starts_with(['+', '-']));
+        let exp_len = digits_at(len + 1 + sign_len);
+        if exp_len > 0 {
+            len += 1 + sign_len + exp_len;
+            is_float = true;
+        }
     }
+    is_float.then_some(len)
 }
 
 #[defun]
@@ -279,6 +363,7 @@ pub(crate) fn defvar<'ob>(
     env: &mut Rt<Env>,
 ) -> Result<Object<'ob>> {
     let value = initvalue.unwrap_or_default();
+    symbol.make_special();
     set(symbol, value, env)
 }
 
@@ -317,6 +402,7 @@ pub(crate) fn aset<'ob>(
     array: Object<'ob>,
     idx: usize,
     newlet: Object<'ob>,
+    cx: &'ob Context,
 ) -> Result<Object<'ob>> {
     match array.untag() {
         ObjectType::Vec(vec) => {
@@ -339,6 +425,11 @@ pub(crate) fn aset<'ob>(
                 Err(anyhow!("index {idx} is out of bounds. Length was {len}"))
             }
         }
+        ObjectType::CharTable(table) => {
+            let chr = idx as i64;
+            table.set_range(chr, chr, newlet, cx)?;
+            Ok(newlet)
+        }
         x => Err(TypeError::new(Type::Sequence, x).into()),
     }
 }
@@ -360,6 +451,7 @@ pub(crate) fn aref<'ob>(array: Object<'ob>, idx: usize, cx: &'ob Context) -> Res
                 Err(anyhow!("index {idx} is out of bounds. Length was {len}"))
             }
         },
+        ObjectType::CharTable(table) => Ok(table.get(idx as i64)),
         ObjectType::String(string) => match string.chars().nth(idx) {
             Some(x) => Ok((i64::from(x as u32)).into()),
             None => {
@@ -396,9 +488,76 @@ fn type_of(object: Object) -> Object {
         ObjectType::String(_) | ObjectType::ByteString(_) => sym::STRING.into(),
         ObjectType::SubrFn(_) => sym::SUBR.into(),
         ObjectType::Buffer(_) => sym::BUFFER.into(),
+        ObjectType::Marker(_) => sym::MARKER.into(),
+        ObjectType::CharTable(_) => sym::CHAR_TABLE.into(),
     }
 }
 
+/// Whether `object` is of type `ty`, as used by `cl-typecase`. `ty` is a type
+/// symbol or an `(or ...)`, `(and ...)`, or `(member ...)` form.
+pub(crate) fn cl_type_matches(object: Object, ty: Object, cx: &Context) -> Result<bool> {
+    let is_array = |obj: Object| {
+        matches!(
+            obj.untag(),
+            ObjectType::Vec(_) | ObjectType::String(_) | ObjectType::ByteString(_)
+        )
+    };
+    let matches = match ty.untag() {
+        ObjectType::Symbol(sym) => match sym {
+            sym::TRUE | sym::OTHERWISE => true,
+            sym::NIL => false,
+            sym::NULL => object.is_nil(),
+            sym::ATOM => atom(object),
+            sym::CONS => matches!(object.untag(), ObjectType::Cons(_)),
+            sym::LIST => listp(object),
+            sym::SYMBOL => matches!(object.untag(), ObjectType::Symbol(_)),
+            sym::KEYWORD => keywordp(object),
+            sym::BOOLEAN => matches!(object.untag(), ObjectType::NIL | ObjectType::TRUE),
+            sym::INTEGER | sym::FIXNUM => matches!(object.untag(), ObjectType::Int(_)),
+            sym::NATNUM => crate::arith::natnump(object),
+            sym::FLOAT => matches!(object.untag(), ObjectType::Float(_)),
+            sym::NUMBER => numberp(object),
+            sym::STRING => {
+                matches!(object.untag(), ObjectType::String(_) | ObjectType::ByteString(_))
+            }
+            sym::VECTOR => matches!(object.untag(), ObjectType::Vec(_)),
+            sym::ARRAY => is_array(object),
+            sym::SEQUENCE => listp(object) || is_array(object),
+            sym::HASH_TABLE => matches!(object.untag(), ObjectType::HashTable(_)),
+            sym::BUFFER => matches!(object.untag(), ObjectType::Buffer(_)),
+            sym::MARKER => markerp(object),
+            sym::CHAR_TABLE => char_table_p(object),
+            sym::FUNCTION => functionp(object, cx),
+            _ => bail!("Unknown type {sym}"),
+        },
+        ObjectType::Cons(cons) => {
+            let mut types = cons.cdr().as_list()?.fallible();
+            match cons.car().untag() {
+                ObjectType::Symbol(sym::OR) => {
+                    while let Some(ty) = types.next()? {
+                        if cl_type_matches(object, ty, cx)? {
+                            return Ok(true);
+                        }
+                    }
+                    false
+                }
+                ObjectType::Symbol(sym::AND) => {
+                    while let Some(ty) = types.next()? {
+                        if !cl_type_matches(object, ty, cx)? {
+                            return Ok(false);
+                        }
+                    }
+                    true
+                }
+                ObjectType::Symbol(sym::MEMBER) => types.any(|x| Ok(crate::fns::eql(x, object)))?,
+                _ => bail!("Unknown type {ty}"),
+            }
+        }
+        _ => bail!("Unknown type {ty}"),
+    };
+    Ok(matches)
+}
+
 #[defun]
 pub(crate) fn indirect_function<'ob>(object: Object<'ob>, cx: &'ob Context) -> Object<'ob> {
     match object.untag() {
@@ -410,13 +569,30 @@ pub(crate) fn indirect_function<'ob>(object: Object<'ob>, cx: &'ob Context) -> O
     }
 }
 
+/// Mark `feature` as provided. Any functions that `eval-after-load'
+/// registered for the feature in `after-load-alist' are then called.
 #[defun]
-pub(crate) fn provide<'ob>(feature: Symbol<'ob>, _subfeatures: Option<&Cons>) -> Symbol<'ob> {
-    let mut features = features().lock().unwrap();
+pub(crate) fn provide<'ob>(
+    feature: &Rto<Gc<Symbol>>,
+    _subfeatures: Option<&Rto<Object>>,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Symbol<'ob>> {
+    let feat = feature.untag(cx);
     // TODO: SYMBOL - need to trace this
-    let feat = unsafe { feature.with_lifetime() };
-    features.insert(feat);
-    feature
+    features().lock().unwrap().insert(unsafe { feat.with_lifetime() });
+    let alist = env.vars.get(sym::AFTER_LOAD_ALIST).map_or(NIL, |x| x.bind(cx));
+    let entry = crate::fns::assq(feat.into(), alist.try_into()?)?;
+    if let ObjectType::Cons(entry) = entry.untag() {
+        if let ObjectType::Cons(funcs) = entry.cdr().untag() {
+            rooted_iter!(funcs, funcs, cx);
+            while let Some(func) = funcs.next()? {
+                let func = func.try_as()?;
+                call!(func; env, cx)?;
+            }
+        }
+    }
+    Ok(feature.untag(cx))
 }
 
 #[defun]
@@ -484,6 +660,150 @@ fn symbol_with_pos_p(_sym: Object) -> bool {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::interpreter::assert_lisp;
+
+    #[test]
+    fn test_defalias() {
+        assert_lisp("(defalias 'alias-test-lambda #'(lambda (x) (* x 2)))", "alias-test-lambda");
+        assert_lisp(
+            "(progn (defalias 'alias-test-lambda #'(lambda (x) (* x 2))) (alias-test-lambda 4))",
+            "8",
+        );
+        assert_lisp(
+            "(progn (defalias 'alias-test-car 'car) (list (alias-test-car '(1 2)) (symbol-function 'alias-test-car)))",
+            "(1 car)",
+        );
+        assert_lisp(
+            "(progn (defalias 'alias-test-1 'car) (defalias 'alias-test-2 'alias-test-1) (alias-test-2 '(3 4)))",
+            "3",
+        );
+        assert_lisp(
+            "(progn (defalias 'alias-test-doc 'car \"Take the car.\") (get 'alias-test-doc 'function-documentation))",
+            "\"Take the car.\"",
+        );
+    }
+
+    #[test]
+    fn test_keywords() {
+        assert_lisp(":foo", ":foo");
+        assert_lisp("(eq :foo :foo)", "t");
+        assert_lisp("(eq (intern \":foo\") :foo)", "t");
+        assert_lisp("(keywordp :foo)", "t");
+        assert_lisp("(keywordp 'foo)", "nil");
+        assert_lisp("(symbol-value :foo)", ":foo");
+        assert_lisp("(boundp :foo)", "t");
+        assert_lisp("(condition-case nil (set :foo 1) (error 'constant))", "constant");
+        assert_lisp("(let ((x :key)) (eq x :key))", "t");
+    }
+
+    #[test]
+    fn test_symbol_function() {
+        let setup = "(defalias 'sf-test-mac (cons 'macro #'(lambda (x) x))) (defalias 'sf-test-alias 'sf-test-mac)";
+        let run = |form| format!("(progn {setup} {form})");
+        assert_lisp(&run("(car (symbol-function 'sf-test-mac))"), "macro");
+        // The raw cell of an alias is the symbol it points to
+        assert_lisp(&run("(symbol-function 'sf-test-alias)"), "sf-test-mac");
+        assert_lisp(&run("(car (indirect-function 'sf-test-alias))"), "macro");
+        assert_lisp(
+            &run("(eq (indirect-function 'sf-test-alias) (symbol-function 'sf-test-mac))"),
+            "t",
+        );
+        assert_lisp("(eq (symbol-function 'car) (indirect-function 'car))", "t");
+        // An unset function cell is nil, like in Emacs, not a void-function error
+        assert_lisp("(symbol-function 'sf-test-unbound)", "nil");
+        assert_lisp(
+            "(condition-case nil (list (fboundp 'sf-test-unbound) (symbol-function 'sf-test-unbound)) (void-function 'void))",
+            "(nil nil)",
+        );
+        assert_lisp("(or (symbol-function 'sf-test-unbound) 'fallback)", "fallback");
+        assert_lisp("(indirect-function 'sf-test-unbound)", "nil");
+        assert_lisp("(indirect-function 5)", "5");
+    }
+
+    #[test]
+    fn test_functionp() {
+        assert_lisp("(functionp 'car)", "t");
+        assert_lisp("(functionp #'car)", "t");
+        assert_lisp("(functionp #'(lambda (x) x))", "t");
+        assert_lisp("(functionp '(lambda (x) x))", "t");
+        assert_lisp("(progn (defalias 'my-car 'car) (functionp 'my-car))", "t");
+        assert_lisp(
+            "(progn (defalias 'my-mac (cons 'macro #'(lambda (x) x))) (functionp 'my-mac))",
+            "nil",
+        );
+        assert_lisp("(functionp 'if)", "nil");
+        assert_lisp("(functionp nil)", "nil");
+        assert_lisp("(functionp 'not-a-defined-function)", "nil");
+        assert_lisp("(functionp 5)", "nil");
+        assert_lisp("(functionp '(1 2))", "nil");
+    }
+
+    #[test]
+    fn test_number_to_string() {
+        assert_lisp("(number-to-string 42)", "\"42\"");
+        assert_lisp("(number-to-string -7)", "\"-7\"");
+        assert_lisp("(number-to-string 1.5)", "\"1.5\"");
+        assert_lisp("(number-to-string 2.0)", "\"2.0\"");
+    }
+
+    #[test]
+    fn test_string_to_number() {
+        assert_lisp("(string-to-number \"42\")", "42");
+        assert_lisp("(string-to-number \"  -42\")", "-42");
+        assert_lisp("(string-to-number \"1.5\")", "1.5");
+        assert_lisp("(string-to-number \"1e3\")", "1000.0");
+        assert_lisp("(string-to-number \"12abc\")", "12");
+        assert_lisp("(string-to-number \"2.5xyz\")", "2.5");
+        assert_lisp("(string-to-number \"1.\")", "1");
+        assert_lisp("(string-to-number \"abc\")", "0");
+        assert_lisp("(string-to-number \"ff\" 16)", "255");
+        assert_lisp("(string-to-number \"1.5\" 16)", "1");
+    }
+
+    #[test]
+    fn test_symbol_name() {
+        assert_lisp("(symbol-name 'foo)", "\"foo\"");
+        assert_lisp("(symbol-name nil)", "\"nil\"");
+        assert_lisp("(eq (intern (symbol-name 'foo)) 'foo)", "t");
+        assert_lisp("(eq (intern (symbol-name nil)) nil)", "t");
+        assert_lisp("(let ((s (make-symbol \"foo\"))) (eq (intern (symbol-name s)) s))", "nil");
+    }
+
+    #[test]
+    fn test_record() {
+        assert_lisp("(recordp (record 'point 1 2))", "t");
+        assert_lisp("(type-of (record 'point 1 2))", "point");
+        assert_lisp(
+            "(let ((r (record 'point 1 2))) (list (aref r 0) (aref r 1) (aref r 2)))",
+            "(point 1 2)",
+        );
+        assert_lisp(
+            "(let ((r (record 'point 1 2))) (aset r 2 5) (list (aref r 1) (aref r 2)))",
+            "(1 5)",
+        );
+        assert_lisp(
+            "(condition-case nil (aref (record 'point 1 2) 3) (error 'out-of-range))",
+            "out-of-range",
+        );
+        assert_lisp("(recordp [point 1 2])", "nil");
+        assert_lisp("(recordp (record 'empty))", "t");
+    }
+
+    #[test]
+    fn test_provide() {
+        assert_lisp("(featurep 'test-provide-feature)", "nil");
+        assert_lisp(
+            "(let ((ran nil) (after-load-alist nil))
+               (setq after-load-alist (list (list 'test-provide-feature #'(lambda () (setq ran (cons 'a ran))) #'(lambda () (setq ran (cons 'b ran))))))
+               (list (provide 'test-provide-feature) (featurep 'test-provide-feature) ran))",
+            "(test-provide-feature t (b a))",
+        );
+        assert_lisp(
+            "(let ((after-load-alist (list (list 'other-feature #'(lambda () (error \"ran\"))))))
+               (provide 'test-provide-unrelated))",
+            "test-provide-unrelated",
+        );
+    }
 
     #[test]
     fn test_ash() {
@@ -498,7 +818,17 @@ mod test {
 defsym!(MANY);
 defsym!(INTEGER);
 defsym!(SYMBOL);
+defsym!(OTHERWISE);
+defsym!(KEYWORD);
+defsym!(BOOLEAN);
+defsym!(FIXNUM);
+defsym!(NATNUM);
+defsym!(NUMBER);
+defsym!(ARRAY);
+defsym!(SEQUENCE);
 defsym!(COMPILED_FUNCTION);
 defsym!(HASH_TABLE);
 defsym!(BUFFER);
+defsym!(MARKER);
+defsym!(CHAR_TABLE);
 defsym!(SUBR);
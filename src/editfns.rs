@@ -20,7 +20,7 @@ defvar!(MESSAGE_NAME);
 defvar!(MESSAGE_TYPE, "new message");
 
 #[defun]
-fn format(string: &str, objects: &[Object]) -> Result<String> {
+pub(crate) fn format(string: &str, objects: &[Object]) -> Result<String> {
     let mut result = String::new();
     let mut arguments = objects.iter();
     let mut remaining = string;
@@ -15,7 +15,7 @@ use crate::rooted_iter;
 use anyhow::{anyhow, bail, ensure, Result};
 use fallible_iterator::FallibleIterator;
 use fallible_streaming_iterator::FallibleStreamingIterator;
-use rune_core::macros::{bail_err, call, list, root};
+use rune_core::macros::{bail_err, call, list, rebind, root};
 use rune_macros::defun;
 use std::fmt::{Display, Formatter};
 
@@ -179,9 +179,12 @@ fn run_hooks<'ob>(hooks: ArgSlice, env: &mut Rt<Env>, cx: &'ob mut Context) -> R
                 if let Some(val) = env.vars.get(sym) {
                     let val = val.bind(cx);
                     match val.untag() {
-                        ObjectType::Cons(hook_list) => {
+                        ObjectType::Cons(hook_list) if !crate::data::functionp(val, cx) => {
                             rooted_iter!(hooks, hook_list, cx);
                             while let Some(hook) = hooks.next()? {
+                                if hook.bind(cx) == sym::TRUE {
+                                    continue;
+                                }
                                 let func = hook.try_as()?;
                                 call!(func; env, cx)?;
                             }
@@ -201,33 +204,79 @@ fn run_hooks<'ob>(hooks: ArgSlice, env: &mut Rt<Env>, cx: &'ob mut Context) -> R
     Ok(NIL)
 }
 
+/// Call `func` with a copy of the arguments at the top of the stack.
+fn call_with_args<'ob>(
+    func: &Rto<Function>,
+    args: ArgSlice,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let beg = env.stack.len() - args.len();
+    env.stack.extend_as_vec_from_within(beg..);
+    let frame = &mut CallFrame::new_with_args(env, args.len());
+    func.call(frame, None, cx).map_err(Into::into)
+}
+
 #[defun]
 fn run_hook_with_args<'ob>(
     hook: &Rto<Object>,
     args: ArgSlice,
     env: &mut Rt<Env>,
     cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    run_hook_until(hook, args, false, env, cx)?;
+    Ok(NIL)
+}
+
+/// Like `run-hook-with-args`, but stop at the first function that returns
+/// non-nil and return that value.
+#[defun]
+fn run_hook_with_args_until_success<'ob>(
+    hook: &Rto<Object>,
+    args: ArgSlice,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    run_hook_until(hook, args, true, env, cx)
+}
+
+/// Call each function in `hook` with `args`. If `until_success` is set, stop
+/// at the first non-nil result and return it.
+fn run_hook_until<'ob>(
+    hook: &Rto<Object>,
+    args: ArgSlice,
+    until_success: bool,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
 ) -> Result<Object<'ob>> {
     match hook.untag(cx) {
         ObjectType::Symbol(sym) => {
             if let Some(val) = env.vars.get(sym) {
                 let val = val.bind(cx);
                 match val.untag() {
-                    ObjectType::Cons(hook_list) => {
+                    ObjectType::Cons(hook_list) if !crate::data::functionp(val, cx) => {
                         rooted_iter!(hooks, hook_list, cx);
                         while let Some(hook) = hooks.next()? {
+                            // `t' refers to the global value of a buffer-local hook
+                            if hook.bind(cx) == sym::TRUE {
+                                continue;
+                            }
                             let func: &Rto<Function> = hook.try_as()?;
-                            let beg = env.stack.len() - args.len();
-                            env.stack.extend_as_vec_from_within(beg..);
-                            let frame = &mut CallFrame::new_with_args(env, args.len());
-                            func.call(frame, None, cx)?;
+                            let result = call_with_args(func, args, env, cx)?;
+                            if until_success && result != NIL {
+                                root!(result, cx);
+                                return Ok(result.bind(cx));
+                            }
                         }
                     }
                     ObjectType::NIL => {}
                     _ => {
                         let func: Function = val.try_into()?;
                         root!(func, cx);
-                        call!(func; env, cx)?;
+                        let result = call_with_args(func, args, env, cx)?;
+                        if until_success {
+                            return Ok(result);
+                        }
                     }
                 }
             }
@@ -371,6 +420,126 @@ fn func_arity<'ob>(function: Function, cx: &'ob Context) -> Result<&'ob Cons> {
     }
 }
 
+/// Return the `(interactive SPEC)` form of `cmd`, or nil if it is not a
+/// command. Only interpreted functions and the `interactive-form` symbol
+/// property can hold a spec.
+#[defun]
+fn interactive_form<'ob>(cmd: Object<'ob>, env: &Rt<Env>, cx: &'ob Context) -> Result<Object<'ob>> {
+    let cmd = match cmd.untag() {
+        ObjectType::Symbol(sym) => {
+            let spec = crate::data::get(sym, sym::INTERACTIVE_FORM, env, cx);
+            if spec != NIL {
+                return Ok(spec);
+            }
+            match sym.follow_indirect(cx) {
+                Some(func) => func.into(),
+                None => return Ok(NIL),
+            }
+        }
+        _ => cmd,
+    };
+    let ObjectType::Cons(func) = cmd.untag() else { return Ok(NIL) };
+    let body_pos = match func.car().untag() {
+        ObjectType::Symbol(sym::CLOSURE) => 3,
+        ObjectType::Symbol(sym::LAMBDA) => 2,
+        _ => return Ok(NIL),
+    };
+    // The spec comes after the docstring and any declarations
+    for form in func.elements().skip(body_pos) {
+        match form?.untag() {
+            ObjectType::String(_) => {}
+            ObjectType::Cons(form) if form.car() == sym::DECLARE => {}
+            ObjectType::Cons(form) if form.car() == sym::INTERACTIVE => return Ok(form.into()),
+            _ => break,
+        }
+    }
+    Ok(NIL)
+}
+
+/// Call `function` as a command. There is no UI to read arguments from, so
+/// the spec must either be empty or a form that evaluates to the argument
+/// list.
+#[defun]
+fn call_interactively<'ob>(
+    function: &Rto<Object>,
+    _record_flag: Option<&Rto<Object>>,
+    _keys: Option<&Rto<Object>>,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let spec = interactive_form(function.bind(cx), env, cx)?;
+    let ObjectType::Cons(spec) = spec.untag() else {
+        bail!("Wrong type argument: commandp, {function}")
+    };
+    let args = match spec.cdr().untag() {
+        ObjectType::Cons(spec) => match spec.car().untag() {
+            ObjectType::NIL => NIL,
+            ObjectType::String(codes) if codes.is_empty() => NIL,
+            ObjectType::String(codes) => bail!("Interactive codes are not supported: {codes}"),
+            _ => {
+                let form = spec.car();
+                root!(form, cx);
+                rebind!(crate::interpreter::eval_toplevel(form, true, env, cx)?, cx)
+            }
+        },
+        _ => NIL,
+    };
+    let len = env.stack.len();
+    for arg in args.as_list()? {
+        let arg = cx.bind(arg?);
+        env.stack.push(arg);
+    }
+    let arg_count = env.stack.len() - len;
+    let func: Function = function.bind(cx).try_into()?;
+    root!(func, cx);
+    let frame = &mut CallFrame::new_with_args(env, arg_count);
+    func.call(frame, None, cx).map_err(Into::into)
+}
+
+#[defun]
+fn commandp(
+    function: Object,
+    _for_call_interactively: Option<Object>,
+    env: &Rt<Env>,
+    cx: &Context,
+) -> Result<bool> {
+    Ok(interactive_form(function, env, cx)? != NIL)
+}
+
+/// Execute `cmd` as an editor command, reading its arguments through
+/// [`call_interactively`]. Keyboard macros are not supported.
+#[defun]
+fn command_execute<'ob>(
+    cmd: &Rto<Object>,
+    record_flag: Option<&Rto<Object>>,
+    keys: Option<&Rto<Object>>,
+    _special: Option<&Rto<Object>>,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    if let ObjectType::String(_) | ObjectType::Vec(_) = cmd.untag(cx) {
+        bail!("Keyboard macros are not supported: {cmd}");
+    }
+    call_interactively(cmd, record_flag, keys, env, cx)
+}
+
+/// Return non-nil if the calling function was called interactively. Commands
+/// are never run from the command loop yet, so this is always nil for every
+/// `kind` (`any', `interactive' or nil).
+#[defun]
+fn called_interactively_p(_kind: Option<Object>) -> bool {
+    false
+}
+
+/// Call `function` on the backtrace frame `nframes` above `base`. Backtraces
+/// are not recorded yet, so there is never a frame and this returns nil. That
+/// is enough for subr.el's `called-interactively-p` to return nil.
+#[defun]
+#[allow(non_snake_case)]
+fn backtrace_frame__internal(_function: Object, _nframes: Object, _base: Object) -> bool {
+    false
+}
+
 #[defun]
 #[allow(non_snake_case)]
 fn internal__define_uninitialized_variable<'ob>(
@@ -381,12 +550,150 @@ fn internal__define_uninitialized_variable<'ob>(
     NIL
 }
 
+/// Transfer control to the innermost `catch' for `tag', making it return
+/// `value'. Tags are compared with `eq'.
 #[defun]
-fn signal(mut error_symbol: Object, data: Object, env: &mut Rt<Env>) -> Result<bool> {
-    if error_symbol.is_nil() && data.is_nil() {
+fn throw(tag: Object, value: Object, env: &mut Rt<Env>, cx: &Context) -> Result<bool> {
+    // Need to check now that there is a catch, because we may have a
+    // condition-case along the unwind path
+    if env.catch_stack.iter().any(|x| x.bind(cx).ptr_eq(tag)) {
+        Err(EvalError::throw(tag, value, env).into())
+    } else {
+        bail!("No catch for {tag}")
+    }
+}
+
+/// Signal an error. If `debug-on-error' is non-nil, the function in
+/// `debugger' is called with `error' and the error object before any handler
+/// is run.
+#[defun]
+fn signal(
+    error_symbol: &Rto<Object>,
+    data: &Rto<Object>,
+    env: &mut Rt<Env>,
+    cx: &mut Context,
+) -> Result<bool> {
+    let mut error_symbol = error_symbol.bind(cx);
+    if error_symbol.is_nil() && data.bind(cx).is_nil() {
         error_symbol = sym::ERROR.into();
     }
-    Err(EvalError::signal(error_symbol, data, env).into())
+    root!(error_symbol, cx);
+    call_debugger(error_symbol, data, env, cx)?;
+    Err(EvalError::signal(error_symbol.bind(cx), data.bind(cx), env).into())
+}
+
+/// Call the function in `debugger' if `debug-on-error' is set. This is done
+/// by `signal' and when a Rust error comes out of a subr, before any handler
+/// runs. Errors raised by the evaluator itself, like calling a void function,
+/// do not come through a subr and do not reach the debugger.
+fn call_debugger(
+    error_symbol: &Rto<Object>,
+    data: &Rto<Object>,
+    env: &mut Rt<Env>,
+    cx: &mut Context,
+) -> Result<()> {
+    if env.vars.get(sym::DEBUG_ON_ERROR).is_none_or(|x| x.bind(cx).is_nil()) {
+        return Ok(());
+    }
+    let debugger = env.vars.get(sym::DEBUGGER).map_or(NIL, |x| x.bind(cx));
+    if debugger.is_nil() {
+        return Ok(());
+    }
+    let debugger: Function = debugger.try_into()?;
+    root!(debugger, cx);
+    let error: Object = Cons::new(error_symbol.bind(cx), data.bind(cx), cx).into();
+    root!(error, cx);
+    // Errors signaled by the debugger itself should not enter it again
+    env.varbind(sym::DEBUG_ON_ERROR, NIL, cx);
+    let kind: Object = sym::ERROR.into();
+    let result = call!(debugger, kind, error.bind(cx); env, cx).map(|_| ());
+    env.unbind(1, cx);
+    result.map_err(Into::into)
+}
+
+/// The conditions an error symbol belongs to, from its `error-conditions'
+/// property. Error symbols without the property are treated as children of
+/// `error'.
+fn error_conditions<'ob>(error: Object<'ob>, env: &Rt<Env>, cx: &'ob Context) -> Vec<Object<'ob>> {
+    let conditions = match error.untag() {
+        ObjectType::Symbol(sym) => crate::data::get(sym, sym::ERROR_CONDITIONS, env, cx),
+        _ => NIL,
+    };
+    match conditions.untag() {
+        ObjectType::Cons(list) => list.elements().map_while(Result::ok).collect(),
+        _ => vec![error, sym::ERROR.into()],
+    }
+}
+
+/// Whether a `condition-case' handler for `condition' catches `err'.
+/// `condition' is a condition symbol or a list of them, and `t' catches any
+/// error.
+pub(crate) fn handler_matches(
+    condition: Object,
+    err: &EvalError,
+    env: &Rt<Env>,
+    cx: &Context,
+) -> Result<bool> {
+    let conditions = match err.error {
+        ErrorType::Throw(_) => return Ok(false),
+        ErrorType::Signal(id) => {
+            let Some((error, _)) = env.get_exception(id) else {
+                unreachable!("Exception not found")
+            };
+            Some(error_conditions(error.bind(cx), env, cx))
+        }
+        // TODO: errors from rust don't have conditions yet, so they are
+        // caught by any handler
+        ErrorType::Err(_) => None,
+    };
+    let matches = |condition: Object| {
+        condition == sym::TRUE || conditions.as_ref().is_none_or(|x| x.contains(&condition))
+    };
+    match condition.untag() {
+        ObjectType::NIL => Ok(false),
+        ObjectType::Symbol(_) => Ok(matches(condition)),
+        ObjectType::Cons(list) => {
+            for condition in list {
+                if matches(condition?) {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        _ => bail!("Invalid condition handler: {condition}"),
+    }
+}
+
+#[defun]
+fn define_error(
+    name: Symbol,
+    message: Object,
+    parent: Option<Object>,
+    env: &mut Rt<Env>,
+    cx: &Context,
+) -> Result<()> {
+    let parents: Vec<Object> = match parent {
+        None => vec![sym::ERROR.into()],
+        Some(parent) => match parent.untag() {
+            ObjectType::Cons(parents) => parents.elements().collect::<Result<_, _>>()?,
+            _ => vec![parent],
+        },
+    };
+    let mut conditions: Vec<Object> = vec![name.into()];
+    for parent in parents {
+        let inherited = error_conditions(parent, env, cx);
+        for condition in std::iter::once(parent).chain(inherited) {
+            if !conditions.contains(&condition) {
+                conditions.push(condition);
+            }
+        }
+    }
+    let conditions = crate::alloc::list(&conditions, cx);
+    env.set_prop(name, sym::ERROR_CONDITIONS, conditions);
+    if !message.is_nil() {
+        env.set_prop(name, sym::ERROR_MESSAGE, message);
+    }
+    Ok(())
 }
 
 #[defun]
@@ -433,9 +740,25 @@ impl Rto<Function<'_>> {
                 crate::bytecode::call(f, arg_cnt, name, frame, cx)
                     .map_err(|e| e.add_trace(name, frame.arg_slice()))
             }
-            FunctionType::SubrFn(f) => {
-                (*f).call(arg_cnt, frame, cx).map_err(|e| add_trace(e, name, frame.arg_slice()))
-            }
+            FunctionType::SubrFn(f) => match (*f).call(arg_cnt, frame, cx) {
+                Ok(x) => Ok(rebind!(x, cx)),
+                Err(e) => {
+                    let e = match e.downcast::<EvalError>() {
+                        Ok(e) => e,
+                        // An error raised in Rust. These are seen as `(error
+                        // . MESSAGE)' by handlers, so the debugger gets the same.
+                        Err(e) => {
+                            let message: Object = cx.add(e.to_string().trim_end());
+                            root!(message, cx);
+                            let error: Object = sym::ERROR.into();
+                            root!(error, cx);
+                            call_debugger(error, message, frame, cx)?;
+                            EvalError::new(e)
+                        }
+                    };
+                    Err(e.add_trace(name, frame.arg_slice()))
+                }
+            },
             FunctionType::Cons(_) => {
                 crate::interpreter::call_closure(self.try_as().unwrap(), arg_cnt, name, frame, cx)
                     .map_err(|e| e.add_trace(name, frame.arg_slice()))
@@ -484,6 +807,18 @@ defsym!(CONDITION_CASE);
 defsym!(UNWIND_PROTECT);
 defsym!(SAVE_EXCURSION);
 defsym!(SAVE_CURRENT_BUFFER);
+defsym!(SAVE_MATCH_DATA);
+defsym!(WITH_OUTPUT_TO_STRING);
+defsym!(CL_ASSERT);
+defsym!(CL_CHECK_TYPE);
+defsym!(CL_BLOCK);
+defsym!(CL_RETURN);
+defsym!(CL_RETURN_FROM);
+defsym!(CL_DOTIMES);
+defsym!(CL_DOLIST);
+defsym!(CL_MULTIPLE_VALUE_BIND);
+defsym!(EVAL_WHEN_COMPILE);
+defsym!(EVAL_AND_COMPILE);
 defsym!(WHILE);
 defsym!(INLINE);
 defsym!(PROGN);
@@ -492,17 +827,421 @@ defsym!(PROG2);
 defsym!(SETQ);
 defsym!(DEFCONST);
 defsym!(COND);
+defsym!(CL_TYPECASE);
+defsym!(CL_ETYPECASE);
+defsym!(CL_CASE);
+defsym!(CASE);
 defsym!(LET);
 defsym!(LET_STAR, "let*");
 defsym!(IF);
 defsym!(AND);
 defsym!(OR);
 defsym!(INTERACTIVE);
+defsym!(DECLARE);
 defsym!(CATCH);
-defsym!(THROW);
 defsym!(ERROR);
 defsym!(DEBUG);
 defsym!(VOID_VARIABLE);
+defsym!(ERROR_CONDITIONS);
+defsym!(CL_ASSERTION_FAILED);
+defsym!(WRONG_TYPE_ARGUMENT);
+
+defsym!(KW_BEFORE);
+defsym!(KW_AFTER);
+defsym!(KW_AROUND);
+defsym!(ADVICE_ARGS, "advice--args");
+defsym!(ADVICE_STACK, "advice--stack");
+
+/// Build a closure that calls `function` as `how` advice around `inner`.
+fn advice_wrapper<'ob>(
+    how: Object<'ob>,
+    function: Object<'ob>,
+    inner: Object<'ob>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    let args = sym::ADVICE_ARGS;
+    let quote = |x| list![sym::QUOTE, x; cx];
+    let call = |x| list![sym::APPLY, quote(x), args; cx];
+    let body = match how.untag() {
+        ObjectType::Symbol(sym::KW_BEFORE) => list![sym::PROGN, call(function), call(inner); cx],
+        ObjectType::Symbol(sym::KW_AFTER) => list![sym::PROG1, call(inner), call(function); cx],
+        ObjectType::Symbol(sym::KW_AROUND) => {
+            list![sym::APPLY, quote(function), quote(inner), args; cx]
+        }
+        _ => bail!("Unsupported advice type: {how}"),
+    };
+    Ok(list![sym::CLOSURE, list![true; cx], list![sym::AND_REST, args; cx], body; cx])
+}
+
+/// Set the function cell of `symbol` to `original` wrapped in each piece of
+/// advice in `advice`, from innermost to outermost. The original definition
+/// and the advice are stored on the `advice--stack` property so the wrapper
+/// can be rebuilt when advice is removed.
+fn install_advice<'ob>(
+    symbol: Symbol,
+    original: Object<'ob>,
+    advice: &[Object<'ob>],
+    env: &mut Rt<Env>,
+    cx: &'ob Context,
+) -> Result<()> {
+    if advice.is_empty() {
+        crate::data::fset(symbol, original)?;
+        env.set_prop(symbol, sym::ADVICE_STACK, NIL);
+        return Ok(());
+    }
+    let mut wrapper = original;
+    for entry in advice {
+        let entry: &Cons = (*entry).try_into()?;
+        wrapper = advice_wrapper(entry.car(), entry.cdr(), wrapper, cx)?;
+    }
+    crate::data::fset(symbol, wrapper)?;
+    let stack = crate::fns::slice_into_list(advice, None, cx);
+    env.set_prop(symbol, sym::ADVICE_STACK, Cons::new(original, stack, cx).into());
+    Ok(())
+}
+
+/// Return the unadvised definition of `symbol` and the advice currently
+/// applied to it.
+fn current_advice<'ob>(
+    symbol: Symbol,
+    env: &Rt<Env>,
+    cx: &'ob Context,
+) -> Result<(Object<'ob>, Vec<Object<'ob>>)> {
+    match crate::data::get(symbol, sym::ADVICE_STACK, env, cx).untag() {
+        ObjectType::Cons(stack) => {
+            let advice = stack.cdr().as_list()?.collect::<Result<_, _>>()?;
+            Ok((stack.car(), advice))
+        }
+        _ => match symbol.func(cx) {
+            Some(func) => Ok((func.into(), Vec::new())),
+            None => bail!("Void function: {symbol}"),
+        },
+    }
+}
+
+/// Whether the `(how . function)` advice entry `entry` applies `function`.
+fn is_advice_for(entry: Object, function: Object) -> bool {
+    matches!(entry.untag(), ObjectType::Cons(entry) if crate::fns::equal(entry.cdr(), function))
+}
+
+#[defun]
+fn advice_add<'ob>(
+    symbol: Symbol,
+    how: Object<'ob>,
+    function: Object<'ob>,
+    _props: Option<Object>,
+    env: &mut Rt<Env>,
+    cx: &'ob Context,
+) -> Result<bool> {
+    ensure!(
+        [sym::KW_BEFORE, sym::KW_AFTER, sym::KW_AROUND].iter().any(|x| how == *x),
+        "Unsupported advice type: {how}"
+    );
+    let (original, mut advice) = current_advice(symbol, env, cx)?;
+    if !advice.iter().any(|entry| is_advice_for(*entry, function)) {
+        advice.push(Cons::new(how, function, cx).into());
+        install_advice(symbol, original, &advice, env, cx)?;
+    }
+    Ok(false)
+}
+
+#[defun]
+fn advice_remove(
+    symbol: Symbol,
+    function: Object,
+    env: &mut Rt<Env>,
+    cx: &Context,
+) -> Result<bool> {
+    if crate::data::get(symbol, sym::ADVICE_STACK, env, cx).is_nil() {
+        return Ok(false);
+    }
+    let (original, mut advice) = current_advice(symbol, env, cx)?;
+    advice.retain(|entry| !is_advice_for(*entry, function));
+    install_advice(symbol, original, &advice, env, cx)?;
+    Ok(false)
+}
 
 defvar!(DEBUG_ON_ERROR, false);
+defvar!(DEBUGGER);
+defvar!(EXECUTING_KBD_MACRO);
 defvar!(INTERNAL_MAKE_INTERPRETED_CLOSURE_FUNCTION);
+
+#[cfg(test)]
+mod test {
+    use crate::interpreter::assert_lisp;
+
+    #[test]
+    fn test_func_arity() {
+        assert_lisp("(func-arity #'(lambda (a b) a))", "(2 . 2)");
+        assert_lisp("(func-arity #'(lambda (a &optional b c) a))", "(1 . 3)");
+        assert_lisp("(func-arity #'(lambda (&rest args) args))", "(0 . many)");
+        assert_lisp("(func-arity #'(lambda (a &optional b &rest c) a))", "(1 . many)");
+        assert_lisp("(func-arity 'car)", "(1 . 1)");
+        assert_lisp("(func-arity #'list)", "(0 . many)");
+        assert_lisp("(progn (defalias 'my-car 'car) (func-arity 'my-car))", "(1 . 1)");
+    }
+
+    #[test]
+    fn test_apply_symbol() {
+        // subr
+        assert_lisp("(apply '+ 1 '(2 3))", "6");
+        assert_lisp("(funcall '+ 1 2)", "3");
+        // lambda
+        assert_lisp(
+            "(progn (defalias 'test-apply-double #'(lambda (x) (* x 2))) (apply 'test-apply-double '(4)))",
+            "8",
+        );
+        assert_lisp("(progn (defalias 'test-funcall-double #'(lambda (x) (* x 2))) (funcall 'test-funcall-double 5))", "10");
+        // alias chain
+        assert_lisp(
+            "(progn (defalias 'test-apply-add #'+) (defalias 'test-apply-add2 'test-apply-add) (apply 'test-apply-add2 1 '(2 3)))",
+            "6",
+        );
+        assert_lisp(
+            "(progn (defalias 'test-funcall-add #'+) (defalias 'test-funcall-add2 'test-funcall-add) (funcall 'test-funcall-add2 1 2))",
+            "3",
+        );
+    }
+
+    #[test]
+    fn test_advice() {
+        // :before runs before the original
+        assert_lisp(
+            "(progn (setq log nil)
+                    (defalias 'adv-f #'(lambda (x) (setq log (cons (list 'orig x) log)) x))
+                    (advice-add 'adv-f :before #'(lambda (x) (setq log (cons (list 'before x) log))))
+                    (adv-f 1)
+                    log)",
+            "((orig 1) (before 1))",
+        );
+        // :after runs after the original and does not change the result
+        assert_lisp(
+            "(progn (setq log nil)
+                    (defalias 'adv-f #'(lambda (x) (setq log (cons 'orig log)) x))
+                    (advice-add 'adv-f :after #'(lambda (x) (setq log (cons 'after log)) 99))
+                    (list (adv-f 1) log))",
+            "(1 (after orig))",
+        );
+        // :around receives the original function and can change the result
+        assert_lisp(
+            "(progn (defalias 'adv-f #'(lambda (x) (* x 2)))
+                    (advice-add 'adv-f :around #'(lambda (orig x) (1+ (funcall orig x))))
+                    (adv-f 5))",
+            "11",
+        );
+        // advice stacks, and removing it restores the original
+        assert_lisp(
+            "(progn (defalias 'adv-f #'(lambda (x) x))
+                    (defalias 'adv-inc #'(lambda (orig x) (1+ (funcall orig x))))
+                    (defalias 'adv-dbl #'(lambda (orig x) (* 2 (funcall orig x))))
+                    (advice-add 'adv-f :around 'adv-inc)
+                    (advice-add 'adv-f :around 'adv-dbl)
+                    (advice-add 'adv-f :around 'adv-dbl)
+                    (let ((advised (adv-f 3)))
+                      (advice-remove 'adv-f 'adv-dbl)
+                      (let ((partial (adv-f 3)))
+                        (advice-remove 'adv-f 'adv-inc)
+                        (list advised partial (adv-f 3)))))",
+            "(8 4 3)",
+        );
+        assert_lisp("(condition-case nil (advice-add 'car :override 'cdr) (error 'err))", "err");
+    }
+
+    #[test]
+    fn test_run_hooks() {
+        assert_lisp(
+            "(progn (setq log nil)
+                    (setq my-hook (list #'(lambda () (setq log (cons 'first log)))
+                                        #'(lambda () (setq log (cons 'second log)))))
+                    (run-hooks 'my-hook)
+                    log)",
+            "(second first)",
+        );
+        assert_lisp(
+            "(progn (setq log nil)
+                    (setq my-hook #'(lambda () (setq log (cons 'single log))))
+                    (run-hooks 'my-hook)
+                    log)",
+            "(single)",
+        );
+        assert_lisp("(progn (setq my-hook nil) (run-hooks 'my-hook 'unbound-hook))", "nil");
+    }
+
+    #[test]
+    fn test_run_hook_with_args() {
+        assert_lisp(
+            "(progn (setq log nil)
+                    (setq my-hook (list #'(lambda (x y) (setq log (cons (list 'first x y) log)))
+                                        t
+                                        #'(lambda (x y) (setq log (cons (list 'second x y) log)))))
+                    (run-hook-with-args 'my-hook 1 2)
+                    log)",
+            "((second 1 2) (first 1 2))",
+        );
+        assert_lisp(
+            "(progn (setq log nil)
+                    (setq my-hook #'(lambda (x) (setq log (cons x log))))
+                    (run-hook-with-args 'my-hook 7)
+                    log)",
+            "(7)",
+        );
+        assert_lisp("(run-hook-with-args 'unbound-hook 1)", "nil");
+    }
+
+    #[test]
+    fn test_run_hook_with_args_until_success() {
+        assert_lisp(
+            "(progn (setq log nil)
+                    (setq my-hook (list #'(lambda (x) (setq log (cons 'first log)) nil)
+                                        #'(lambda (x) (setq log (cons 'second log)) (* x 2))
+                                        #'(lambda (x) (setq log (cons 'third log)) x)))
+                    (list (run-hook-with-args-until-success 'my-hook 3) log))",
+            "(6 (second first))",
+        );
+        assert_lisp(
+            "(progn (setq my-hook #'(lambda (x) (+ x 1)))
+                    (run-hook-with-args-until-success 'my-hook 1))",
+            "2",
+        );
+        assert_lisp("(run-hook-with-args-until-success 'unbound-hook 1)", "nil");
+    }
+
+    #[test]
+    fn test_special_variable_p() {
+        assert_lisp("(progn (defvar special-test-a 1) (special-variable-p 'special-test-a))", "t");
+        assert_lisp("(special-variable-p 'special-test-undeclared)", "nil");
+        assert_lisp("(progn (setq special-test-b 1) (special-variable-p 'special-test-b))", "nil");
+        // declaring an already bound variable makes it special without
+        // changing its value
+        assert_lisp(
+            "(progn (setq special-test-c 1) (defvar special-test-c 2) (list (special-variable-p 'special-test-c) special-test-c))",
+            "(t 1)",
+        );
+        // special variables are bound dynamically
+        assert_lisp(
+            "(progn (defvar special-test-d 1)
+                    (defalias 'special-test-get-d #'(lambda () special-test-d))
+                    (let ((special-test-d 2)) (special-test-get-d)))",
+            "2",
+        );
+    }
+
+    #[test]
+    fn test_define_error() {
+        let define = "(define-error 'my-error \"My error\")";
+        let catch = |handler| {
+            format!("(progn {define} (condition-case err (signal 'my-error '(1)) ({handler} (car err))))")
+        };
+        assert_lisp(&catch("error"), "my-error");
+        assert_lisp(&catch("my-error"), "my-error");
+        assert_lisp(&catch("(arith-error my-error)"), "my-error");
+        assert_lisp(&catch("t"), "my-error");
+        assert_lisp(
+            &format!("(progn {define} (get 'my-error 'error-conditions))"),
+            "(my-error error)",
+        );
+        assert_lisp(
+            &format!("(progn {define} (error-message-string '(my-error 1)))"),
+            r#""My error: 1""#,
+        );
+        let hierarchy = "(progn (define-error 'parent-error \"Parent\")
+                                (define-error 'child-error \"Child\" 'parent-error)
+                                (define-error 'other-error \"Other\"))";
+        assert_lisp(
+            &format!("(progn {hierarchy} (get 'child-error 'error-conditions))"),
+            "(child-error parent-error error)",
+        );
+        assert_lisp(
+            &format!(
+                "(progn {hierarchy}
+                   (condition-case nil
+                       (condition-case nil (signal 'child-error nil) (other-error 'other))
+                     (parent-error 'parent)))"
+            ),
+            "parent",
+        );
+        assert_lisp("(catch 'tag (condition-case nil (throw 'tag 1) (error 2)))", "1");
+    }
+
+    #[test]
+    fn test_debug_on_error() {
+        let debug = |enabled| {
+            format!(
+                "(progn (setq debug-seen nil)
+                        (list (let ((debug-on-error {enabled})
+                                    (debugger #'(lambda (kind err) (setq debug-seen (list kind err)))))
+                                (condition-case nil (signal 'wrong-type-argument '(1)) (error 'handled)))
+                              debug-seen))"
+            )
+        };
+        assert_lisp(&debug("t"), "(handled (error (wrong-type-argument 1)))");
+        assert_lisp(&debug("nil"), "(handled nil)");
+        // no debugger set
+        assert_lisp(
+            "(let ((debug-on-error t)) (condition-case nil (signal 'error nil) (error 'handled)))",
+            "handled",
+        );
+        // errors raised in Rust reach the debugger too
+        assert_lisp(
+            "(progn (setq debug-seen nil)
+                    (list (let ((debug-on-error t)
+                                (debugger #'(lambda (kind err) (setq debug-seen (list kind (car err))))))
+                            (condition-case nil (car 1) (error 'handled)))
+                          debug-seen))",
+            "(handled (error error))",
+        );
+        // errors inside the debugger do not enter it again
+        assert_lisp(
+            "(let ((debug-on-error t) (debugger #'(lambda (kind err) (signal 'arith-error nil))))
+               (condition-case err (signal 'error '(1)) (error (car err))))",
+            "arith-error",
+        );
+    }
+
+    #[test]
+    fn test_call_interactively() {
+        assert_lisp("(interactive-form #'(lambda () (interactive) 1))", "(interactive)");
+        assert_lisp(
+            "(interactive-form #'(lambda (x) \"Doc.\" (interactive (list 2)) x))",
+            "(interactive (list 2))",
+        );
+        assert_lisp("(interactive-form '(lambda () (interactive \"p\") 1))", "(interactive \"p\")");
+        assert_lisp("(interactive-form #'(lambda () 1))", "nil");
+        assert_lisp("(interactive-form #'(lambda () \"(interactive)\"))", "nil");
+        assert_lisp("(interactive-form 'car)", "nil");
+        let command =
+            "(defalias 'ci-test-cmd #'(lambda (&optional n) (interactive) (list 'ran n)))";
+        assert_lisp(&format!("(progn {command} (interactive-form 'ci-test-cmd))"), "(interactive)");
+        assert_lisp(&format!("(progn {command} (call-interactively 'ci-test-cmd))"), "(ran nil)");
+        assert_lisp("(call-interactively #'(lambda (a b) (interactive (list 1 2)) (+ a b)))", "3");
+        assert_lisp(
+            "(condition-case nil (call-interactively 'car) (error 'not-command))",
+            "not-command",
+        );
+    }
+
+    #[test]
+    fn test_called_interactively_p() {
+        assert_lisp("(called-interactively-p 'any)", "nil");
+        assert_lisp("(called-interactively-p 'interactive)", "nil");
+        assert_lisp("(called-interactively-p nil)", "nil");
+        assert_lisp("(called-interactively-p)", "nil");
+        let command =
+            "(defalias 'cip-test-cmd #'(lambda () (interactive) (called-interactively-p 'any)))";
+        assert_lisp(&format!("(progn {command} (call-interactively 'cip-test-cmd))"), "nil");
+        assert_lisp("(backtrace-frame--internal #'list 1 'car)", "nil");
+    }
+
+    #[test]
+    fn test_command_execute() {
+        let command = "(defalias 'ce-test-cmd #'(lambda () (interactive) 'executed))";
+        assert_lisp(&format!("(progn {command} (commandp 'ce-test-cmd))"), "t");
+        assert_lisp(&format!("(progn {command} (command-execute 'ce-test-cmd))"), "executed");
+        assert_lisp("(commandp 'car)", "nil");
+        assert_lisp("(condition-case nil (command-execute 'car) (error 'failed))", "failed");
+        assert_lisp(
+            "(condition-case nil (command-execute 'ce-test-void) (error 'failed))",
+            "failed",
+        );
+    }
+}
@@ -7,9 +7,17 @@ use crate::{
         object::{Number, NumberType, Object},
     },
 };
-
+use anyhow::{bail, ensure, Result};
+use rune_core::macros::list;
 use rune_macros::defun;
 
+/// Convert an already rounded float to an integer. NaN and infinity have no
+/// integer value.
+fn float_to_int(float: f64) -> Result<i64> {
+    ensure!(float.is_finite(), "Arithmetic overflow error");
+    Ok(float as i64)
+}
+
 #[inline(always)]
 fn coerce(arg: Number) -> f64 {
     match arg.untag() {
@@ -19,22 +27,59 @@ fn coerce(arg: Number) -> f64 {
 }
 
 #[defun]
-fn floor(arg: Number, divisor: Option<Number>) -> i64 {
-    let num = match divisor {
-        Some(div) => arg.val() / div.val(),
-        None => arg.val(),
-    };
-    match num {
-        NumberValue::Int(i) => i,
-        NumberValue::Float(f) => f.floor() as i64,
+fn floor(arg: Number, divisor: Option<Number>) -> Result<i64> {
+    Ok(divide(arg, divisor, true)?.0)
+}
+
+/// Divide `x` by `y` (default 1) and round the quotient down if `floor` is
+/// true, or toward zero otherwise. Returns the quotient and the remainder
+/// `x - quotient * y`.
+fn divide(x: Number, y: Option<Number>, floor: bool) -> Result<(i64, NumberValue)> {
+    let y = y.map_or(NumberValue::Int(1), |y| y.val());
+    match (x.val(), y) {
+        (NumberValue::Int(x), NumberValue::Int(y)) => {
+            ensure!(y != 0, "Arithmetic error");
+            let Some(mut quotient) = x.checked_div(y) else { bail!("Arithmetic overflow error") };
+            let mut remainder = x - quotient * y;
+            if floor && remainder != 0 && (remainder < 0) != (y < 0) {
+                quotient -= 1;
+                remainder += y;
+            }
+            Ok((quotient, NumberValue::Int(remainder)))
+        }
+        (x, y) => {
+            let float = |n| match n {
+                NumberValue::Int(i) => i as f64,
+                NumberValue::Float(f) => f,
+            };
+            let (x, y) = (float(x), float(y));
+            let quotient = if floor { (x / y).floor() } else { (x / y).trunc() };
+            Ok((float_to_int(quotient)?, NumberValue::Float(x - quotient * y)))
+        }
     }
 }
 
+/// Return the list `(QUOTIENT REMAINDER)` of dividing `x` by `y`, rounding
+/// down.
+#[defun]
+fn cl_floor<'ob>(x: Number, y: Option<Number>, cx: &'ob Context) -> Result<Object<'ob>> {
+    let (quotient, remainder) = divide(x, y, true)?;
+    Ok(list![quotient, remainder; cx])
+}
+
+/// Return the list `(QUOTIENT REMAINDER)` of dividing `x` by `y`, rounding
+/// toward zero.
+#[defun]
+fn cl_truncate<'ob>(x: Number, y: Option<Number>, cx: &'ob Context) -> Result<Object<'ob>> {
+    let (quotient, remainder) = divide(x, y, false)?;
+    Ok(list![quotient, remainder; cx])
+}
+
 #[defun]
-fn ceiling(arg: Number) -> i64 {
+fn ceiling(arg: Number) -> Result<i64> {
     match arg.untag() {
-        NumberType::Int(i) => i,
-        NumberType::Float(f) => f.ceil() as i64,
+        NumberType::Int(i) => Ok(i),
+        NumberType::Float(f) => float_to_int(f.ceil()),
     }
 }
 
@@ -47,18 +92,18 @@ fn fceiling(arg: Number) -> f64 {
 }
 
 #[defun]
-fn round(arg: Number) -> i64 {
+fn round(arg: Number) -> Result<i64> {
     match arg.untag() {
-        NumberType::Int(i) => i,
-        NumberType::Float(f) => f.round() as i64,
+        NumberType::Int(i) => Ok(i),
+        NumberType::Float(f) => float_to_int(f.round()),
     }
 }
 
 #[defun]
-fn truncate(arg: Number) -> i64 {
+fn truncate(arg: Number) -> Result<i64> {
     match arg.untag() {
-        NumberType::Int(i) => i,
-        NumberType::Float(f) => f.trunc() as i64,
+        NumberType::Int(i) => Ok(i),
+        NumberType::Float(f) => float_to_int(f.trunc()),
     }
 }
 
@@ -124,9 +169,15 @@ fn exp(arg: Number) -> f64 {
 
 #[defun]
 fn expt(x: Number, y: Number) -> NumberValue {
-    // If either is a float, we use the float version
+    // If either is a float, or the exponent is negative, we use the float
+    // version. Integer results that overflow are also computed as floats.
     match (x.untag(), y.untag()) {
-        (NumberType::Int(x), NumberType::Int(y)) => NumberValue::Int(x.pow(y as u32)),
+        (NumberType::Int(base), NumberType::Int(exp)) if exp >= 0 => {
+            match u32::try_from(exp).ok().and_then(|exp| base.checked_pow(exp)) {
+                Some(result) => NumberValue::Int(result),
+                None => NumberValue::Float((base as f64).powf(exp as f64)),
+            }
+        }
         _ => {
             let x = coerce(x);
             let y = coerce(y);
@@ -189,3 +240,72 @@ fn frexp<'ob>(x: Number, cx: &'ob Context) -> Object<'ob> {
     let (significand, exponent) = frexp_f(f);
     Cons::new(significand, exponent, cx).into()
 }
+
+#[cfg(test)]
+mod test {
+    use crate::interpreter::assert_lisp;
+
+    #[test]
+    fn test_expt() {
+        assert_lisp("(expt 2 10)", "1024");
+        assert_lisp("(expt -3 3)", "-27");
+        assert_lisp("(expt 5 0)", "1");
+        assert_lisp("(expt 2 -1)", "0.5");
+        assert_lisp("(expt 2.0 3)", "8.0");
+        assert_lisp("(expt 4 0.5)", "2.0");
+        assert_lisp("(integerp (expt 2 100))", "nil");
+    }
+
+    #[test]
+    fn test_abs() {
+        assert_lisp("(abs -5)", "5");
+        assert_lisp("(abs 5)", "5");
+        assert_lisp("(abs -2.5)", "2.5");
+    }
+
+    #[test]
+    fn test_float_math() {
+        assert_lisp("(sqrt 16)", "4.0");
+        assert_lisp("(isnan (sqrt -1))", "t");
+        assert_lisp("(sin 0)", "0.0");
+        assert_lisp("(cos 0)", "1.0");
+        assert_lisp("(exp 0)", "1.0");
+        assert_lisp("(log 1)", "0.0");
+        assert_lisp("(log 8 2)", "3.0");
+    }
+
+    #[test]
+    fn test_special_floats() {
+        assert_lisp("(isnan 0.0e+NaN)", "t");
+        assert_lisp("(isnan (/ 0.0 0.0))", "t");
+        assert_lisp("(isnan 1.0e+INF)", "nil");
+        assert_lisp("(isnan 1)", "nil");
+        assert_lisp("(= (/ 1.0 0) 1.0e+INF)", "t");
+        assert_lisp("(= (- (/ 1.0 0)) -1.0e+INF)", "t");
+        assert_lisp("(number-to-string (* 2 1.0e+INF))", "\"1.0e+INF\"");
+        assert_lisp("(condition-case nil (truncate 1.0e+INF) (error 'overflow))", "overflow");
+        assert_lisp("(condition-case nil (round 0.0e+NaN) (error 'overflow))", "overflow");
+        assert_lisp("(floor 2.5)", "2");
+    }
+
+    #[test]
+    fn test_cl_floor() {
+        assert_lisp("(cl-floor 7 2)", "(3 1)");
+        assert_lisp("(cl-floor -7 2)", "(-4 1)");
+        assert_lisp("(cl-floor 7 -2)", "(-4 -1)");
+        assert_lisp("(cl-floor -7 -2)", "(3 -1)");
+        assert_lisp("(cl-floor 6 3)", "(2 0)");
+        assert_lisp("(cl-floor 5)", "(5 0)");
+        assert_lisp("(cl-truncate 7 2)", "(3 1)");
+        assert_lisp("(cl-truncate -7 2)", "(-3 -1)");
+        assert_lisp("(cl-truncate 7 -2)", "(-3 1)");
+        assert_lisp("(cl-truncate -7 -2)", "(3 -1)");
+        assert_lisp("(cl-floor 7.5 2)", "(3 1.5)");
+        assert_lisp("(cl-truncate -2.5)", "(-2 -0.5)");
+        assert_lisp("(floor -7 2)", "-4");
+        assert_lisp("(condition-case nil (cl-floor 1 0) (error 'div-by-zero))", "div-by-zero");
+        assert_lisp("(condition-case nil (cl-truncate 1 0) (error 'div-by-zero))", "div-by-zero");
+        assert_lisp("(condition-case nil (cl-floor 1.0 0) (error 'div-by-zero))", "div-by-zero");
+        assert_lisp("(condition-case nil (floor 1 0) (error 'div-by-zero))", "div-by-zero");
+    }
+}
@@ -1,13 +1,13 @@
 //! General purpose lisp functions
 use crate::{
     core::{
-        cons::Cons,
-        env::{sym, Env},
+        cons::{Cons, ConsError},
+        env::{sym, ArgSlice, CallFrame, Env},
         error::{Type, TypeError},
-        gc::{Context, Rt, Rto},
+        gc::{Context, Rt, Rto, Slot},
         object::{
-            Function, Gc, HashTable, IntoObject, LispHashTable, LispString, LispVec, List,
-            ListType, Object, ObjectType, OptionalFlag, Symbol, WithLifetime, NIL,
+            int_to_char, Function, Gc, HashTable, HashTest, IntoObject, LispHashTable, LispString,
+            LispVec, List, ListType, Object, ObjectType, OptionalFlag, Symbol, WithLifetime, NIL,
         },
     },
     data::aref,
@@ -18,13 +18,23 @@ use anyhow::{bail, ensure, Result};
 use fallible_iterator::FallibleIterator;
 use fallible_streaming_iterator::FallibleStreamingIterator;
 use rune_core::macros::{call, list, rebind, root};
-use rune_macros::defun;
+use rune_macros::{defun, Trace};
 
 #[defun]
 fn identity(arg: Object) -> Object {
     arg
 }
 
+#[defun]
+fn ignore(_arguments: &[Object]) -> bool {
+    false
+}
+
+#[defun]
+fn always(_arguments: &[Object]) -> bool {
+    true
+}
+
 pub(crate) fn slice_into_list<'ob>(
     slice: &[Object<'ob>],
     tail: Option<Object<'ob>>,
@@ -73,18 +83,21 @@ fn equal_including_properties<'ob>(o1: Object<'ob>, o2: Object<'ob>) -> bool {
     equal(o1, o2)
 }
 
+/// Return the value of `prop` in `plist`. Like Emacs this never signals an
+/// error: a malformed plist is only searched up to the point where it stops
+/// being a proper list.
 #[defun]
-fn plist_get<'ob>(plist: Object<'ob>, prop: Object<'ob>) -> Result<Object<'ob>> {
-    let Ok(plist) = List::try_from(plist) else { return Ok(NIL) };
-    // TODO: this function should never fail. Need to implement safe iterator
-    let mut iter = plist.elements();
+fn plist_get<'ob>(plist: Object<'ob>, prop: Object<'ob>) -> Object<'ob> {
+    let Ok(plist) = List::try_from(plist) else { return NIL };
+    // stop at a non-nil tail or a cycle instead of failing
+    let mut iter = plist.elements().map_while(Result::ok);
     while let Some(cur_prop) = iter.next() {
-        let Some(value) = iter.next() else { return Ok(NIL) };
-        if eq(cur_prop?, prop) {
-            return Ok(value?);
+        let Some(value) = iter.next() else { return NIL };
+        if eq(cur_prop, prop) {
+            return value;
         }
     }
-    Ok(NIL)
+    NIL
 }
 
 #[defun]
@@ -118,12 +131,30 @@ fn string_to_multibyte(string: &LispString) -> &LispString {
     string
 }
 
+#[defun]
+fn string_to_list<'ob>(string: &str, cx: &'ob Context) -> Object<'ob> {
+    let chars: Vec<Object> = string.chars().map(|chr| (chr as i64).into()).collect();
+    crate::alloc::list(&chars, cx)
+}
+
+#[defun]
+fn string_to_vector<'ob>(string: &str, cx: &'ob Context) -> Gc<&'ob LispVec> {
+    let chars: Vec<Object> = string.chars().map(|chr| (chr as i64).into()).collect();
+    chars.into_obj(cx)
+}
+
 #[defun]
 fn string_search(needle: &str, haystack: &str, start_pos: Option<usize>) -> Option<usize> {
     let start = start_pos.unwrap_or(0);
     haystack[start..].find(needle).map(|x| x + start)
 }
 
+#[defun]
+fn string_replace(from: &str, to: &str, in_string: &str) -> Result<String> {
+    ensure!(!from.is_empty(), "wrong-length-argument: {from:?}");
+    Ok(in_string.replace(from, to))
+}
+
 #[defun]
 pub(crate) fn mapcar<'ob>(
     function: &Rto<Function>,
@@ -136,30 +167,157 @@ pub(crate) fn mapcar<'ob>(
         ObjectType::NIL => Ok(NIL),
         ObjectType::Cons(cons) => {
             rooted_iter!(iter, cons, cx);
-            root!(outputs, new(Vec), cx);
+            root!(list, new(ListBuilder), cx);
             while let Some(obj) = iter.next()? {
-                let output = call!(function, obj; env, cx)?;
-                outputs.push(output);
+                let output = rebind!(call!(function, obj; env, cx)?, cx);
+                list.push(output, cx)?;
             }
-            // TODO: remove this intermediate vector
-            Ok(slice_into_list(Rt::bind_slice(outputs, cx), None, cx))
+            Ok(list.head(cx))
         }
         ObjectType::ByteFn(fun) => {
             let len = fun.len();
             root!(fun, cx);
-            root!(outputs, new(Vec), cx);
+            root!(list, new(ListBuilder), cx);
             for i in 0..len {
                 let val = fun.bind(cx).index(i, cx).unwrap();
-                let output = call!(function, val; env, cx)?;
-                outputs.push(output);
+                let output = rebind!(call!(function, val; env, cx)?, cx);
+                list.push(output, cx)?;
             }
-            // TODO: remove this intermediate vector
-            Ok(slice_into_list(Rt::bind_slice(outputs, cx), None, cx))
+            Ok(list.head(cx))
         }
         _ => Err(TypeError::new(Type::Sequence, sequence).into()),
     }
 }
 
+/// A list that is built by adding elements to the end. The head and tail are
+/// kept rooted, so the list can be built while calling into lisp.
+#[derive(Default, Trace)]
+pub(crate) struct ListBuilder<'ob> {
+    head: Slot<Object<'ob>>,
+    tail: Slot<Object<'ob>>,
+}
+
+// RootedListBuilder created by #[derive(Trace)]
+impl RootedListBuilder<'_> {
+    /// Add `obj` to the end of the list.
+    pub(crate) fn push<'ob>(&mut self, obj: Object<'ob>, cx: &'ob Context) -> Result<()> {
+        let cell: Object = Cons::new1(obj, cx).into();
+        match self.tail.bind(cx).untag() {
+            ObjectType::Cons(last) => last.set_cdr(cell)?,
+            _ => self.head.set(cell),
+        }
+        self.tail.set(cell);
+        Ok(())
+    }
+
+    /// The list built so far.
+    pub(crate) fn head<'ob>(&self, cx: &'ob Context) -> Object<'ob> {
+        self.head.bind(cx)
+    }
+}
+
+/// Collect the elements of a list, vector, or string into a `Vec`.
+pub(crate) fn sequence_elements<'ob>(sequence: Object<'ob>) -> Result<Vec<Object<'ob>>> {
+    match sequence.untag() {
+        ObjectType::NIL => Ok(Vec::new()),
+        ObjectType::Cons(cons) => Ok(cons.elements().collect::<Result<_, _>>()?),
+        ObjectType::Vec(vec) => Ok(vec.iter().map(|x| x.get()).collect()),
+        ObjectType::String(string) => Ok(string.chars().map(|c| (c as i64).into()).collect()),
+        _ => Err(TypeError::new(Type::Sequence, sequence).into()),
+    }
+}
+
+#[defun]
+fn cl_map<'ob>(
+    result_type: &Rto<Object>,
+    function: &Rto<Function>,
+    sequence: &Rto<Object>,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let result_type = result_type.bind(cx);
+    ensure!(
+        [sym::NIL, sym::LIST, sym::VECTOR, sym::STRING]
+            .iter()
+            .any(|x| result_type == *x),
+        "cl-map: unsupported result type {result_type}"
+    );
+    root!(result_type, cx);
+    let elements = sequence_elements(sequence.bind(cx))?;
+    root!(elements, cx);
+    root!(outputs, new(ListBuilder), cx);
+    for i in 0..elements.len() {
+        let output = rebind!(call!(function, &elements[i]; env, cx)?, cx);
+        outputs.push(output, cx)?;
+    }
+    let outputs = outputs.head(cx);
+    match result_type.bind(cx).untag() {
+        ObjectType::Symbol(sym::LIST) => Ok(outputs),
+        ObjectType::Symbol(sym::VECTOR) => Ok(cx.add(sequence_elements(outputs)?)),
+        ObjectType::Symbol(sym::STRING) => {
+            let mut string = String::new();
+            for output in outputs.as_list()? {
+                let output = output?;
+                let chr: i64 = output.try_into()?;
+                match u32::try_from(chr).ok().and_then(char::from_u32) {
+                    Some(chr) => string.push(chr),
+                    None => bail!(TypeError::new(Type::Char, output)),
+                }
+            }
+            Ok(cx.add(string))
+        }
+        _ => Ok(NIL),
+    }
+}
+
+/// Call `function' with the first element of each of `sequences', then with
+/// the second elements and so on, and return a list of the results. Mapping
+/// stops at the end of the shortest sequence.
+#[defun]
+fn cl_mapcar<'ob>(
+    function: &Rto<Function>,
+    sequences: ArgSlice,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let sequences = Rt::bind_slice(env.stack.arg_slice(sequences), cx);
+    let sequences: Vec<_> =
+        sequences.iter().map(|x| sequence_elements(*x)).collect::<Result<_>>()?;
+    let count = sequences.len();
+    let len = sequences.iter().map(Vec::len).min().unwrap_or(0);
+    // The arguments of each call, one after another
+    let args: Vec<Object> =
+        (0..len).flat_map(|i| sequences.iter().map(move |seq| seq[i])).collect();
+    root!(args, cx);
+    root!(list, new(ListBuilder), cx);
+    for i in 0..len {
+        let frame = &mut CallFrame::new(env);
+        for arg in &args[i * count..(i + 1) * count] {
+            frame.push_arg(arg);
+        }
+        let output = rebind!(function.call(frame, None, cx)?, cx);
+        list.push(output, cx)?;
+    }
+    Ok(list.head(cx))
+}
+
+/// Return an alist pairing each of `keys' with the corresponding element of
+/// `values', stopping at the end of the shorter list. The pairs are added to
+/// the front of `alist'.
+#[defun]
+fn cl_pairlis<'ob>(
+    keys: List<'ob>,
+    values: List<'ob>,
+    alist: Option<Object<'ob>>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    let mut pairs = Vec::new();
+    for (key, value) in keys.elements().zip(values.elements()) {
+        pairs.push(Cons::new(key?, value?, cx).into());
+    }
+    Ok(slice_into_list(&pairs, alist, cx))
+}
+
 #[defun]
 pub(crate) fn mapc<'ob>(
     function: &Rto<Function>,
@@ -222,34 +380,66 @@ pub(crate) fn mapconcat(
 }
 
 #[defun]
-pub(crate) fn nreverse(seq: List) -> Result<Object> {
-    let mut prev = NIL;
-    for tail in seq.conses() {
-        let tail = tail?;
-        tail.set_cdr(prev)?;
-        prev = tail.into();
+pub(crate) fn nreverse<'ob>(seq: Object<'ob>, cx: &'ob Context) -> Result<Object<'ob>> {
+    match seq.untag() {
+        ObjectType::NIL => Ok(NIL),
+        ObjectType::Cons(list) => {
+            let mut prev = NIL;
+            for tail in list.conses() {
+                let tail = tail?;
+                tail.set_cdr(prev)?;
+                prev = tail.into();
+            }
+            Ok(prev)
+        }
+        ObjectType::Vec(vec) => {
+            let cells = vec.try_mut()?;
+            let len = cells.len();
+            for i in 0..len / 2 {
+                let (front, back) = (cells[i].get(), cells[len - 1 - i].get());
+                cells[i].set(back);
+                cells[len - 1 - i].set(front);
+            }
+            Ok(seq)
+        }
+        // Strings are immutable, so they are reversed into a new string
+        ObjectType::String(_) => reverse(seq, cx),
+        _ => Err(TypeError::new(Type::Sequence, seq).into()),
     }
-    Ok(prev)
 }
 
+/// Return a reversed copy of `seq`. The result has the same type as `seq`, so
+/// reversing an empty vector or string gives an empty vector or string.
 #[defun]
-pub(crate) fn reverse<'ob>(seq: List, cx: &'ob Context) -> Result<Object<'ob>> {
-    let mut tail = NIL;
-    for elem in seq {
-        tail = Cons::new(elem?, tail, cx).into();
+pub(crate) fn reverse<'ob>(seq: Object<'ob>, cx: &'ob Context) -> Result<Object<'ob>> {
+    match seq.untag() {
+        ObjectType::NIL => Ok(NIL),
+        ObjectType::Cons(list) => {
+            let mut tail = NIL;
+            for elem in list {
+                tail = Cons::new(elem?, tail, cx).into();
+            }
+            Ok(tail)
+        }
+        ObjectType::Vec(vec) => Ok(cx.add(vec.iter().rev().map(|x| x.get()).collect::<Vec<_>>())),
+        ObjectType::String(string) => Ok(cx.add(string.chars().rev().collect::<String>())),
+        _ => Err(TypeError::new(Type::Sequence, seq).into()),
     }
-    Ok(tail)
 }
 
 #[defun]
 pub(crate) fn nconc<'ob>(lists: &[List<'ob>]) -> Result<Object<'ob>> {
     let mut tail: Option<&Cons> = None;
-    for list in lists {
+    for (idx, list) in lists.iter().enumerate() {
         if let Some(cons) = tail {
             cons.set_cdr((*list).into())?;
         }
-        if let Some(last) = list.conses().last() {
-            tail = Some(last?);
+        // The last list is not walked, so it may be dotted. Empty lists are
+        // skipped and leave the previous tail in place.
+        if idx + 1 < lists.len() {
+            if let Some(last) = list.conses().fallible().last()? {
+                tail = Some(last);
+            }
         }
     }
 
@@ -301,10 +491,16 @@ pub(crate) fn append<'ob>(
     Ok(slice_into_list(&list, None, cx))
 }
 
+/// Iterate over the cons cells of `list`, treating a non-nil tail as the end
+/// of the list rather than an error.
+fn proper_conses<'ob>(list: List<'ob>) -> impl Iterator<Item = Result<&'ob Cons, ConsError>> {
+    list.conses().take_while(|x| !matches!(x, Err(ConsError::NonNilCdr)))
+}
+
 #[defun]
 pub(crate) fn assq<'ob>(key: Object<'ob>, alist: List<'ob>) -> Result<Object<'ob>> {
-    for elem in alist {
-        if let ObjectType::Cons(cons) = elem?.untag() {
+    for elem in proper_conses(alist) {
+        if let ObjectType::Cons(cons) = elem?.car().untag() {
             if eq(key, cons.car()) {
                 return Ok(cons.into());
             }
@@ -315,8 +511,8 @@ pub(crate) fn assq<'ob>(key: Object<'ob>, alist: List<'ob>) -> Result<Object<'ob
 
 #[defun]
 fn rassq<'ob>(key: Object<'ob>, alist: List<'ob>) -> Result<Object<'ob>> {
-    for elem in alist {
-        if let ObjectType::Cons(cons) = elem?.untag() {
+    for elem in proper_conses(alist) {
+        if let ObjectType::Cons(cons) = elem?.car().untag() {
             if eq(key, cons.cdr()) {
                 return Ok(cons.into());
             }
@@ -325,6 +521,26 @@ fn rassq<'ob>(key: Object<'ob>, alist: List<'ob>) -> Result<Object<'ob>> {
     Ok(NIL)
 }
 
+/// Like `assq', but return the value of the matching element (its cdr)
+/// instead of the whole cons. Return nil if there is no match.
+#[defun]
+fn assq_value<'ob>(key: Object<'ob>, alist: List<'ob>) -> Result<Object<'ob>> {
+    Ok(match assq(key, alist)?.untag() {
+        ObjectType::Cons(cons) => cons.cdr(),
+        _ => NIL,
+    })
+}
+
+/// Like `rassq', but return the key of the matching element (its car)
+/// instead of the whole cons. Return nil if there is no match.
+#[defun]
+fn rassq_value<'ob>(key: Object<'ob>, alist: List<'ob>) -> Result<Object<'ob>> {
+    Ok(match rassq(key, alist)?.untag() {
+        ObjectType::Cons(cons) => cons.car(),
+        _ => NIL,
+    })
+}
+
 #[defun]
 pub(crate) fn assoc<'ob>(
     key: &Rto<Object<'ob>>,
@@ -422,7 +638,7 @@ pub(crate) fn delq<'ob>(elt: Object<'ob>, list: List<'ob>) -> Result<Object<'ob>
 }
 
 fn member_of_list<'ob>(elt: Object<'ob>, list: List<'ob>, eq_fn: EqFunc) -> Result<Object<'ob>> {
-    let val = list.conses().fallible().find(|x| Ok(eq_fn(x.car(), elt)))?;
+    let val = fallible_iterator::convert(proper_conses(list)).find(|x| Ok(eq_fn(x.car(), elt)))?;
     match val {
         Some(elem) => Ok(elem.into()),
         None => Ok(NIL),
@@ -444,6 +660,53 @@ pub(crate) fn member<'ob>(elt: Object<'ob>, list: List<'ob>) -> Result<Object<'o
     member_of_list(elt, list, equal)
 }
 
+#[defun]
+fn add_to_list<'ob>(
+    list_var: &Rto<Gc<Symbol>>,
+    element: &Rto<Object>,
+    append: OptionalFlag,
+    compare_fn: Option<&Rto<Object>>,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let symbol = list_var.untag(cx);
+    let Some(value) = env.vars.get(symbol) else { bail!("Void variable: {symbol}") };
+    let list: List = value.bind(cx).try_into()?;
+    let present = match compare_fn {
+        Some(func) => {
+            let func: Function = func.bind(cx).try_into()?;
+            root!(func, cx);
+            rooted_iter!(iter, list, cx);
+            let mut found = false;
+            while let Some(elem) = iter.next()? {
+                if call!(func, element, elem; env, cx)? != NIL {
+                    found = true;
+                    break;
+                }
+            }
+            found
+        }
+        None => member(element.bind(cx), list)? != NIL,
+    };
+    let symbol = list_var.untag(cx);
+    // compare-fn may have unbound the variable
+    let Some(list) = env.vars.get(symbol) else { bail!("Void variable: {symbol}") };
+    let list = list.bind(cx);
+    if present {
+        return Ok(list);
+    }
+    let element = element.bind(cx);
+    let new_list = if append.is_some() {
+        let mut elements = sequence_elements(list)?;
+        elements.push(element);
+        slice_into_list(&elements, None, cx)
+    } else {
+        Cons::new(element, list, cx).into()
+    };
+    env.set_var(symbol, new_list)?;
+    Ok(new_list)
+}
+
 // TODO: Handle sorting vectors
 #[defun]
 fn sort<'ob>(
@@ -482,6 +745,79 @@ fn sort<'ob>(
     }
 }
 
+defsym!(KW_KEY);
+
+/// Sort a list or vector with `predicate`. Vectors are sorted in place, and
+/// lists are rebuilt in sorted order. The sort is stable. The `:key` keyword
+/// argument gives a function applied to each element before comparing.
+#[defun]
+fn cl_sort<'ob>(
+    seq: &Rto<Object>,
+    predicate: &Rto<Function>,
+    keyword_args: ArgSlice,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let keyword_args = Rt::bind_slice(env.stack.arg_slice(keyword_args), cx);
+    ensure!(keyword_args.len() % 2 == 0, "Odd number of keyword arguments to cl-sort");
+    let mut key = NIL;
+    for pair in keyword_args.chunks_exact(2) {
+        match pair[0].untag() {
+            ObjectType::Symbol(sym::KW_KEY) => key = pair[1],
+            kw => bail!("Unsupported keyword argument to cl-sort: {kw}"),
+        }
+    }
+    root!(key, cx);
+    let key: Option<&Rto<Function>> = key.try_as_option()?;
+    let elements = match seq.bind(cx).untag() {
+        ObjectType::NIL | ObjectType::Cons(_) | ObjectType::Vec(_) => {
+            sequence_elements(seq.bind(cx))?
+        }
+        obj => bail!(TypeError::new(Type::Sequence, obj)),
+    };
+    root!(elements, cx);
+    root!(keys, new(Vec), cx);
+    for i in 0..elements.len() {
+        match key {
+            Some(key) => {
+                let value = call!(key, &elements[i]; env, cx)?;
+                keys.push(value);
+            }
+            None => keys.push(&elements[i]),
+        }
+    }
+    let mut order: Vec<usize> = (0..elements.len()).collect();
+    let mut err = None;
+    order.sort_by(|&a, &b| {
+        use std::cmp::Ordering;
+        if err.is_some() {
+            return Ordering::Equal;
+        }
+        match call!(predicate, &keys[a], &keys[b]; env, cx) {
+            Ok(x) if x == NIL => Ordering::Greater,
+            Ok(_) => Ordering::Less,
+            Err(e) => {
+                err = Some(e.into());
+                Ordering::Equal
+            }
+        }
+    });
+    if let Some(e) = err {
+        return Err(e);
+    }
+    let elements = Rt::bind_slice(elements, cx);
+    let sorted: Vec<Object> = order.into_iter().map(|i| elements[i]).collect();
+    match seq.bind(cx).untag() {
+        ObjectType::Vec(vec) => {
+            for (cell, value) in vec.try_mut()?.iter().zip(sorted) {
+                cell.set(value);
+            }
+            Ok(seq.bind(cx))
+        }
+        _ => Ok(slice_into_list(&sorted, None, cx)),
+    }
+}
+
 #[defun]
 pub(crate) fn defvaralias<'ob>(
     new_alias: Symbol<'ob>,
@@ -493,8 +829,11 @@ pub(crate) fn defvaralias<'ob>(
 }
 
 #[defun]
-// TODO: implement
-pub(crate) fn featurep(_feature: Symbol, _subfeature: Option<Symbol>) {}
+pub(crate) fn featurep(feature: Symbol, _subfeature: Option<Object>) -> bool {
+    // TODO: check subfeatures
+    let feat = unsafe { feature.with_lifetime() };
+    crate::data::features().lock().unwrap().contains(&feat)
+}
 
 #[defun]
 pub(crate) fn require<'ob>(
@@ -576,6 +915,39 @@ pub(crate) fn length(sequence: Object) -> Result<usize> {
     Ok(size)
 }
 
+/// The length of `sequence`, except that lists are only walked for `limit`
+/// elements. Circular lists are treated as longer than any limit.
+fn length_up_to(sequence: Object, limit: usize) -> Result<usize> {
+    let ObjectType::Cons(list) = sequence.untag() else { return length(sequence) };
+    let mut count = 0;
+    for cons in list.conses().take(limit) {
+        match cons {
+            Ok(_) => count += 1,
+            Err(ConsError::CircularList) => return Ok(limit),
+            Err(ConsError::NonNilCdr) => break,
+        }
+    }
+    Ok(count)
+}
+
+#[defun(name = "length=")]
+fn length_equal(sequence: Object, length: i64) -> Result<bool> {
+    let Ok(length) = usize::try_from(length) else { return Ok(false) };
+    Ok(length_up_to(sequence, length + 1)? == length)
+}
+
+#[defun(name = "length<")]
+fn length_less(sequence: Object, length: i64) -> Result<bool> {
+    let Ok(length) = usize::try_from(length) else { return Ok(false) };
+    Ok(length_up_to(sequence, length)? < length)
+}
+
+#[defun(name = "length>")]
+fn length_greater(sequence: Object, length: i64) -> Result<bool> {
+    let Ok(length) = usize::try_from(length) else { return Ok(true) };
+    Ok(length_up_to(sequence, length + 1)? > length)
+}
+
 #[defun]
 pub(crate) fn safe_length(sequence: Object) -> usize {
     length(sequence).unwrap_or(0)
@@ -616,6 +988,21 @@ pub(crate) fn elt<'ob>(sequence: Object<'ob>, n: usize, cx: &'ob Context) -> Res
     }
 }
 
+#[defun]
+pub(crate) fn seq_length(sequence: Object) -> Result<usize> {
+    match sequence.untag() {
+        ObjectType::String(x) => Ok(x.chars().count()),
+        _ => length(sequence),
+    }
+}
+
+#[defun]
+pub(crate) fn seq_elt<'ob>(sequence: Object<'ob>, n: i64, cx: &'ob Context) -> Result<Object<'ob>> {
+    let idx = if n < 0 { n + seq_length(sequence)? as i64 } else { n };
+    ensure!(idx >= 0, "Args out of range: {sequence}, {n}");
+    elt(sequence, idx as usize, cx)
+}
+
 #[defun]
 pub(crate) fn string_equal<'ob>(s1: Object<'ob>, s2: Object<'ob>) -> Result<bool> {
     let s1 = match s1.untag() {
@@ -788,25 +1175,50 @@ pub(crate) fn string_version_lessp<'ob>(
 ///////////////
 
 defsym!(KW_TEST);
+defsym!(KW_SIZE);
 defsym!(KW_DOCUMENTATION);
 
+/// The hash table test named by `test`.
+fn hash_test(test: Object) -> Result<HashTest> {
+    match test.untag() {
+        ObjectType::Symbol(sym::EQ) => Ok(HashTest::Eq),
+        ObjectType::Symbol(sym::EQL) => Ok(HashTest::Eql),
+        ObjectType::Symbol(sym::EQUAL) => Ok(HashTest::Equal),
+        _ => bail!("Invalid hash table test: {test}"),
+    }
+}
+
 #[defun]
 pub(crate) fn make_hash_table<'ob>(
     keyword_args: &[Object<'ob>],
     cx: &'ob Context,
 ) -> Result<Object<'ob>> {
     let kw_test_pos = keyword_args.iter().step_by(2).position(|&x| x == sym::KW_TEST);
-    if let Some(i) = kw_test_pos {
-        let Some(val) = keyword_args.get((i * 2) + 1) else {
-            bail!("Missing keyword value for :test")
-        };
-        if *val != sym::EQ && *val != sym::EQUAL && *val != sym::EQL {
-            // TODO: we are currently only using `equal', but eq should be okay
-            bail!("only `eq' and `equal' keywords support for make-hash-table :test. Found {val}");
+    let test = match kw_test_pos {
+        Some(i) => {
+            let Some(val) = keyword_args.get((i * 2) + 1) else {
+                bail!("Missing keyword value for :test")
+            };
+            hash_test(*val)?
         }
-    }
+        None => HashTest::default(),
+    };
+    let kw_size_pos = keyword_args.iter().step_by(2).position(|&x| x == sym::KW_SIZE);
+    let size = match kw_size_pos {
+        Some(i) => {
+            let Some(val) = keyword_args.get((i * 2) + 1) else {
+                bail!("Missing keyword value for :size")
+            };
+            match val.untag() {
+                ObjectType::NIL => 0,
+                ObjectType::Int(size) if size >= 0 => size as usize,
+                _ => bail!("Invalid hash table size: {val}"),
+            }
+        }
+        None => 0,
+    };
     // TODO, the rest of the keywords need to be supported here
-    let map = HashTable::with_hasher(std::hash::BuildHasherDefault::default());
+    let map = HashTable::with_capacity(test, size)?;
     Ok(cx.add(map))
 }
 
@@ -815,6 +1227,79 @@ pub(crate) fn hash_table_p(obj: Object) -> bool {
     matches!(obj.untag(), ObjectType::HashTable(_))
 }
 
+/// Return the test used to compare keys in `table'.
+#[defun]
+fn hash_table_test(table: &LispHashTable) -> Symbol<'static> {
+    match table.test() {
+        HashTest::Eq => sym::EQ,
+        HashTest::Eql => sym::EQL,
+        HashTest::Equal => sym::EQUAL,
+    }
+}
+
+/// Return a copy of `table' with the same test and entries. The keys and
+/// values are shared, but adding or removing entries in one table does not
+/// affect the other.
+#[defun]
+fn copy_hash_table<'ob>(table: &LispHashTable, cx: &'ob Context) -> Gc<&'ob LispHashTable> {
+    cx.add_as(table.copy())
+}
+
+/// Return an alist of the `(KEY . VALUE)' entries in `table'.
+#[defun]
+fn hash_table_to_alist<'ob>(table: &LispHashTable, cx: &'ob Context) -> Object<'ob> {
+    let mut alist = NIL;
+    // Build from the end so the entries stay in insertion order
+    for i in (0..table.len()).rev() {
+        let (key, value) = table.get_index(i).unwrap();
+        alist = Cons::new(Cons::new(key, value, cx), alist, cx).into();
+    }
+    alist
+}
+
+/// Return a new hash table with the entries of `alist', compared with `test'
+/// (`eql' by default). When a key appears more than once, the first entry is
+/// used, as with `assoc'.
+#[defun]
+fn alist_to_hash_table<'ob>(
+    alist: List,
+    test: Option<Object>,
+    cx: &'ob Context,
+) -> Result<Gc<&'ob LispHashTable>> {
+    let test = match test {
+        Some(test) => hash_test(test)?,
+        None => HashTest::default(),
+    };
+    let mut table = HashTable::new(test);
+    for entry in alist {
+        let entry: &Cons = entry?.try_into()?;
+        if table.get(&entry.car()).is_none() {
+            table.insert(entry.car(), entry.cdr());
+        }
+    }
+    Ok(cx.add_as(table))
+}
+
+/// Return the number of entries `table' can hold before it has to grow.
+#[defun]
+fn hash_table_size(table: &LispHashTable) -> usize {
+    table.capacity()
+}
+
+/// Return the fraction of the size of `table' that can be filled before it
+/// grows.
+#[defun]
+fn hash_table_rehash_threshold(_table: &LispHashTable) -> f64 {
+    crate::core::object::HASH_TABLE_REHASH_THRESHOLD
+}
+
+/// Return the factor by which the size of `table' grows when it is full.
+#[defun]
+fn hash_table_rehash_size(_table: &LispHashTable) -> f64 {
+    crate::core::object::HASH_TABLE_REHASH_SIZE
+}
+
+/// Look up `key' in `table', comparing keys with the test of the table.
 #[defun]
 pub(crate) fn gethash<'ob>(
     key: Object<'ob>,
@@ -827,6 +1312,8 @@ pub(crate) fn gethash<'ob>(
     }
 }
 
+/// Associate `key' with `value' in `table'. If a key that matches under the
+/// test of the table is already present, its value is replaced.
 #[defun]
 pub(crate) fn puthash<'ob>(
     key: Object<'ob>,
@@ -926,6 +1413,327 @@ fn substring(string: &str, from: Option<usize>, to: Option<usize>) -> Result<Str
     Ok(new_string.to_owned())
 }
 
+#[defun]
+fn cl_subseq<'ob>(
+    seq: Object<'ob>,
+    start: i64,
+    end: Option<i64>,
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    let elements = sequence_elements(seq)?;
+    let len = elements.len() as i64;
+    let from = if start < 0 { start + len } else { start };
+    let to = match end {
+        Some(end) if end < 0 => end + len,
+        Some(end) => end,
+        None => len,
+    };
+    ensure!(
+        0 <= from && from <= to && to <= len,
+        "Args out of range: {seq}, {start}, {end:?}"
+    );
+    let (from, to) = (from as usize, to as usize);
+    match seq.untag() {
+        ObjectType::Vec(_) => Ok(elements[from..to].to_vec().into_obj(cx).into()),
+        ObjectType::String(string) => {
+            let substring: String = string.chars().skip(from).take(to - from).collect();
+            Ok(cx.add(substring))
+        }
+        _ => Ok(slice_into_list(&elements[from..to], None, cx)),
+    }
+}
+
+defsym!(KW_FROM_END);
+
+#[defun]
+fn cl_remove_duplicates<'ob>(
+    seq: &Rto<Object>,
+    keyword_args: ArgSlice,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let keyword_args = Rt::bind_slice(env.stack.arg_slice(keyword_args), cx);
+    ensure!(
+        keyword_args.len() % 2 == 0,
+        "Odd number of keyword arguments to cl-remove-duplicates"
+    );
+    let mut test = NIL;
+    let mut from_end = false;
+    for pair in keyword_args.chunks_exact(2) {
+        match pair[0].untag() {
+            ObjectType::Symbol(sym::KW_TEST) => test = pair[1],
+            ObjectType::Symbol(sym::KW_FROM_END) => from_end = !pair[1].is_nil(),
+            key => bail!("Unsupported keyword argument to cl-remove-duplicates: {key}"),
+        }
+    }
+    root!(test, cx);
+    let test: Option<&Rto<Function>> = test.try_as_option()?;
+    let elements = sequence_elements(seq.bind(cx))?;
+    root!(elements, cx);
+    let len = elements.len();
+    // By default later duplicates win, so an element is dropped if it matches
+    // anything after it. With `:from-end' the first occurrence is kept instead.
+    let mut keep = vec![true; len];
+    for i in 0..len {
+        let others: Vec<usize> = if from_end {
+            (0..i).filter(|&j| keep[j]).collect()
+        } else {
+            (i + 1..len).collect()
+        };
+        for j in others {
+            let (a, b) = if from_end { (j, i) } else { (i, j) };
+            let duplicate = match test {
+                Some(func) => call!(func, &elements[a], &elements[b]; env, cx)? != NIL,
+                None => eql(elements[a].bind(cx), elements[b].bind(cx)),
+            };
+            if duplicate {
+                keep[i] = false;
+                break;
+            }
+        }
+    }
+    let elements = Rt::bind_slice(elements, cx);
+    let kept: Vec<Object> =
+        elements.iter().zip(keep).filter(|(_, k)| *k).map(|(x, _)| *x).collect();
+    sequence_like(seq.bind(cx), &kept, cx)
+}
+
+/// Build a sequence of the same type as `seq` out of `elements`.
+fn sequence_like<'ob>(
+    seq: Object,
+    elements: &[Object<'ob>],
+    cx: &'ob Context,
+) -> Result<Object<'ob>> {
+    match seq.untag() {
+        ObjectType::Vec(_) => Ok(cx.add(elements.to_vec())),
+        ObjectType::String(_) => {
+            let mut string = String::new();
+            for chr in elements {
+                string.push(int_to_char((*chr).try_into()?)?);
+            }
+            Ok(cx.add(string))
+        }
+        _ => Ok(slice_into_list(elements, None, cx)),
+    }
+}
+
+defsym!(KW_COUNT);
+
+/// Find the elements that match `item`, as used by `cl-remove' and friends.
+/// Elements are compared with `eql', or with the `:test' function called
+/// with `item` and the element. A `:key` function is applied to each element
+/// before comparing, and `:count` limits how many matches are returned.
+fn cl_matches(
+    name: &str,
+    item: &Rto<Object>,
+    elements: &Rt<Vec<Slot<Object>>>,
+    keyword_args: ArgSlice,
+    env: &mut Rt<Env>,
+    cx: &mut Context,
+) -> Result<Vec<bool>> {
+    let keyword_args = Rt::bind_slice(env.stack.arg_slice(keyword_args), cx);
+    ensure!(keyword_args.len() % 2 == 0, "Odd number of keyword arguments to {name}");
+    let (mut test, mut key) = (NIL, NIL);
+    let mut count = None;
+    for pair in keyword_args.chunks_exact(2) {
+        match pair[0].untag() {
+            ObjectType::Symbol(sym::KW_TEST) => test = pair[1],
+            ObjectType::Symbol(sym::KW_KEY) => key = pair[1],
+            ObjectType::Symbol(sym::KW_COUNT) => {
+                count = match pair[1].untag() {
+                    ObjectType::NIL => None,
+                    ObjectType::Int(n) => Some(usize::try_from(n).unwrap_or(0)),
+                    _ => bail!(TypeError::new(Type::Int, pair[1])),
+                }
+            }
+            kw => bail!("Unsupported keyword argument to {name}: {kw}"),
+        }
+    }
+    root!(test, cx);
+    root!(key, cx);
+    let test: Option<&Rto<Function>> = test.try_as_option()?;
+    let key: Option<&Rto<Function>> = key.try_as_option()?;
+    let mut matches = vec![false; elements.len()];
+    for (i, matched) in matches.iter_mut().enumerate() {
+        if count == Some(0) {
+            break;
+        }
+        let value = match key {
+            Some(key) => rebind!(call!(key, &elements[i]; env, cx)?, cx),
+            None => elements[i].bind(cx),
+        };
+        *matched = match test {
+            Some(test) => call!(test, item, value; env, cx)? != NIL,
+            None => eql(item.bind(cx), value),
+        };
+        if *matched {
+            count = count.map(|n| n - 1);
+        }
+    }
+    Ok(matches)
+}
+
+/// Return a copy of `seq' with the elements matching `item' removed. The
+/// `:test', `:key' and `:count' keyword arguments are supported.
+#[defun]
+fn cl_remove<'ob>(
+    item: &Rto<Object>,
+    seq: &Rto<Object>,
+    keyword_args: ArgSlice,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let elements = sequence_elements(seq.bind(cx))?;
+    root!(elements, cx);
+    let matches = cl_matches("cl-remove", item, elements, keyword_args, env, cx)?;
+    let elements = Rt::bind_slice(elements, cx);
+    let kept: Vec<Object> =
+        elements.iter().zip(matches).filter(|(_, m)| !m).map(|(x, _)| *x).collect();
+    sequence_like(seq.bind(cx), &kept, cx)
+}
+
+/// Return a copy of `seq' with the elements matching `old' replaced by `new'.
+/// The `:test', `:key' and `:count' keyword arguments are supported.
+#[defun]
+fn cl_substitute<'ob>(
+    new: &Rto<Object>,
+    old: &Rto<Object>,
+    seq: &Rto<Object>,
+    keyword_args: ArgSlice,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let elements = sequence_elements(seq.bind(cx))?;
+    root!(elements, cx);
+    let matches = cl_matches("cl-substitute", old, elements, keyword_args, env, cx)?;
+    let new = new.bind(cx);
+    let elements = Rt::bind_slice(elements, cx);
+    let replaced: Vec<Object> =
+        elements.iter().zip(matches).map(|(x, m)| if m { new } else { *x }).collect();
+    sequence_like(seq.bind(cx), &replaced, cx)
+}
+
+/// Like `cl-remove', but lists are modified in place by unlinking the
+/// matching elements.
+#[defun]
+fn cl_delete<'ob>(
+    item: &Rto<Object>,
+    seq: &Rto<Object>,
+    keyword_args: ArgSlice,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let ObjectType::Cons(list) = seq.bind(cx).untag() else {
+        return cl_remove(item, seq, keyword_args, env, cx);
+    };
+    let elements = list.elements().collect::<Result<Vec<_>, _>>()?;
+    root!(elements, cx);
+    let matches = cl_matches("cl-delete", item, elements, keyword_args, env, cx)?;
+    let ObjectType::Cons(list) = seq.bind(cx).untag() else { unreachable!() };
+    let mut head = NIL;
+    let mut prev: Option<&Cons> = None;
+    for (cons, matched) in list.conses().zip(matches) {
+        let cons = cons?;
+        match prev {
+            Some(prev) if matched => prev.set_cdr(cons.cdr())?,
+            None if matched => {}
+            _ => {
+                if prev.is_none() {
+                    head = cons.into();
+                }
+                prev = Some(cons);
+            }
+        }
+    }
+    Ok(head)
+}
+
+defsym!(KW_INITIAL_VALUE);
+
+/// Combine the elements of `seq' using the two argument `function', from left
+/// to right. With `:from-end' the elements are combined from right to left,
+/// calling `function' with the element first and the accumulated value
+/// second. `:initial-value' starts the combination, and `:key' is applied to
+/// each element first.
+#[defun]
+fn cl_reduce<'ob>(
+    function: &Rto<Function>,
+    seq: &Rto<Object>,
+    keyword_args: ArgSlice,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let keyword_args = Rt::bind_slice(env.stack.arg_slice(keyword_args), cx);
+    ensure!(keyword_args.len() % 2 == 0, "Odd number of keyword arguments to cl-reduce");
+    let (mut initial, mut key) = (None, NIL);
+    let mut from_end = false;
+    for pair in keyword_args.chunks_exact(2) {
+        match pair[0].untag() {
+            ObjectType::Symbol(sym::KW_INITIAL_VALUE) => initial = Some(pair[1]),
+            ObjectType::Symbol(sym::KW_FROM_END) => from_end = !pair[1].is_nil(),
+            ObjectType::Symbol(sym::KW_KEY) => key = pair[1],
+            kw => bail!("Unsupported keyword argument to cl-reduce: {kw}"),
+        }
+    }
+    let has_initial = initial.is_some();
+    root!(initial, initial.unwrap_or_default(), cx);
+    root!(key, cx);
+    let key: Option<&Rto<Function>> = key.try_as_option()?;
+    let elements = sequence_elements(seq.bind(cx))?;
+    root!(elements, cx);
+    root!(values, new(Vec), cx);
+    for i in 0..elements.len() {
+        match key {
+            Some(key) => {
+                let value = call!(key, &elements[i]; env, cx)?;
+                values.push(value);
+            }
+            None => values.push(&elements[i]),
+        }
+    }
+    let mut order: Box<dyn Iterator<Item = usize>> = if from_end {
+        Box::new((0..values.len()).rev())
+    } else {
+        Box::new(0..values.len())
+    };
+    root!(acc, NIL, cx);
+    if has_initial {
+        acc.set(&*initial);
+    } else {
+        match order.next() {
+            Some(i) => acc.set(&values[i]),
+            None => return Ok(call!(function; env, cx)?),
+        }
+    }
+    for i in order {
+        let result = if from_end {
+            call!(function, &values[i], &*acc; env, cx)?
+        } else {
+            call!(function, &*acc, &values[i]; env, cx)?
+        };
+        let result = rebind!(result, cx);
+        acc.set(result);
+    }
+    Ok(acc.bind(cx))
+}
+
+#[defun]
+fn cl_find_if<'ob>(
+    predicate: &Rto<Function>,
+    seq: &Rto<List>,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let ListType::Cons(cons) = seq.untag(cx) else { return Ok(NIL) };
+    rooted_iter!(elements, cons, cx);
+    while let Some(elem) = elements.next()? {
+        if call!(predicate, elem; env, cx)? != NIL {
+            return Ok(elem.bind(cx));
+        }
+    }
+    Ok(NIL)
+}
+
 defsym!(MD5);
 defsym!(SHA1);
 defsym!(SHA224);
@@ -961,7 +1769,106 @@ fn disable_debug() -> bool {
 
 #[cfg(test)]
 mod test {
+    use super::ListBuilder;
+    use crate::core::{cons::Cons, gc::Context, gc::RootSet};
     use crate::{fns::levenshtein_distance, interpreter::assert_lisp};
+    use rune_core::macros::root;
+
+    #[test]
+    fn test_string_replace() {
+        assert_lisp(r#"(string-replace "a" "bc" "abcabc")"#, r#""bcbcbcbc""#);
+        assert_lisp(r#"(string-replace "foo" "x" "foofoo bar")"#, r#""xx bar""#);
+        assert_lisp(r#"(string-replace "aa" "b" "aaa")"#, r#""ba""#);
+        assert_lisp(r#"(string-replace "z" "y" "abc")"#, r#""abc""#);
+        assert_lisp(r#"(condition-case nil (string-replace "" "x" "abc") (error 'err))"#, "err");
+    }
+
+    #[test]
+    fn test_string_to_list() {
+        assert_lisp(r#"(string-to-list "abc")"#, "(97 98 99)");
+        assert_lisp(r#"(string-to-list "")"#, "nil");
+        assert_lisp(r#"(string-to-list "héλ")"#, "(104 233 955)");
+        assert_lisp(r#"(string-to-vector "abc")"#, "[97 98 99]");
+        assert_lisp(r#"(string-to-vector "")"#, "[]");
+        assert_lisp(r#"(string-to-vector "é😀")"#, "[233 128512]");
+    }
+
+    #[test]
+    fn test_length_compare() {
+        assert_lisp("(length= '(1 2 3) 3)", "t");
+        assert_lisp("(length= '(1 2 3) 2)", "nil");
+        assert_lisp("(length= nil 0)", "t");
+        assert_lisp("(length= [1 2] 2)", "t");
+        assert_lisp(r#"(length= "abc" 3)"#, "t");
+        assert_lisp("(length< '(1 2 3) 4)", "t");
+        assert_lisp("(length< '(1 2 3) 3)", "nil");
+        assert_lisp("(length< '(1 2 3) -1)", "nil");
+        assert_lisp("(length> '(1 2 3) 2)", "t");
+        assert_lisp("(length> '(1 2 3) 3)", "nil");
+        assert_lisp("(length> nil -1)", "t");
+        assert_lisp("(length= '(1 2 . 3) 2)", "t");
+        // circular lists are only walked as far as needed
+        let circular = "(let ((x (list 1 2 3))) (setcdr (nthcdr 2 x) x) x)";
+        assert_lisp(&format!("(length> {circular} 10)"), "t");
+        assert_lisp(&format!("(length< {circular} 10)"), "nil");
+        assert_lisp(&format!("(length= {circular} 10)"), "nil");
+        assert_lisp(&format!("(length> {circular} 1000000000)"), "t");
+    }
+
+    #[test]
+    fn test_seq_length() {
+        assert_lisp("(seq-length nil)", "0");
+        assert_lisp("(seq-length '(1 2 3))", "3");
+        assert_lisp("(seq-length [1 2])", "2");
+        assert_lisp(r#"(seq-length "héllo")"#, "5");
+    }
+
+    #[test]
+    fn test_elt_large_vector() {
+        // Vectors are indexed directly, so this doesn't depend on the index
+        let sum = "(let ((v (make-vector 10000 1)) (i 0) (sum 0))
+                     (aset v 9999 5)
+                     (while (< i 1000)
+                       (setq sum (+ sum (elt v (- 9999 i))) i (1+ i)))
+                     sum)";
+        assert_lisp(sum, "1004");
+        assert_lisp("(elt (make-vector 10000 nil) 9999)", "nil");
+        assert_lisp("(condition-case nil (elt (make-vector 10 nil) 10) (error 'err))", "err");
+        // nth and nthcdr only take lists, like Emacs
+        assert_lisp("(condition-case nil (nth 1 [1 2]) (wrong-type-argument 'err))", "err");
+        assert_lisp("(nthcdr 2 '(1 2 3))", "(3)");
+    }
+
+    #[test]
+    fn test_seq_elt() {
+        assert_lisp("(seq-elt '(1 2 3) 0)", "1");
+        assert_lisp("(seq-elt '(1 2 3) -1)", "3");
+        assert_lisp("(seq-elt [1 2 3] 1)", "2");
+        assert_lisp("(seq-elt [1 2 3] -3)", "1");
+        assert_lisp(r#"(seq-elt "abc" 2)"#, "?c");
+        assert_lisp(r#"(seq-elt "abc" -2)"#, "?b");
+        assert_lisp("(condition-case nil (seq-elt [1 2 3] -4) (error 'err))", "err");
+    }
+
+    #[test]
+    fn test_dotted_tails() {
+        assert_lisp("(memq 2 '(1 2 . 3))", "(2 . 3)");
+        assert_lisp("(memq 4 '(1 2 . 3))", "nil");
+        assert_lisp("(member \"b\" '(\"a\" \"b\" . \"c\"))", "(\"b\" . \"c\")");
+        assert_lisp("(assq 'b '((a . 1) (b . 2) . c))", "(b . 2)");
+        assert_lisp("(assq 'z '((a . 1) . c))", "nil");
+        assert_lisp("(rassq 1 '((a . 1) . c))", "(a . 1)");
+    }
+
+    #[test]
+    fn test_ignore_always() {
+        assert_lisp("(ignore)", "nil");
+        assert_lisp("(ignore 1 'a \"b\")", "nil");
+        assert_lisp("(let ((x 0)) (ignore (setq x 1)) x)", "1");
+        assert_lisp("(always)", "t");
+        assert_lisp("(always nil nil)", "t");
+        assert_lisp("(funcall #'always 1 2 3)", "t");
+    }
 
     #[test]
     fn test_take() {
@@ -972,6 +1879,19 @@ mod test {
     fn test_delq() {
         assert_lisp("(delq 1 '(1 2 3 1 4 1))", "(2 3 4)");
         assert_lisp("(delq t '(t t t))", "nil");
+        assert_lisp("(delq 'a '(a a a b a c))", "(b c)");
+        assert_lisp("(delq 'a '(b a a))", "(b)");
+        assert_lisp("(delq 'a nil)", "nil");
+        assert_lisp("(let ((l (list 'a 'a 'a 'b))) (delq 'a l) l)", "(a a a b)");
+        assert_lisp("(let ((l (list 'b 'a 'c))) (delq 'a l) l)", "(b c)");
+    }
+
+    #[test]
+    fn test_delete() {
+        assert_lisp(r#"(delete "a" (list "a" "a" "a" "b" "a"))"#, r#"("b")"#);
+        assert_lisp("(delete '(1) (list '(1) '(1) '(1)))", "nil");
+        assert_lisp("(delete 1.0 '(1.0 1.0 1.0 2 1.0))", "(2)");
+        assert_lisp("(delete 1 '(2 3))", "(2 3)");
     }
 
     #[test]
@@ -988,6 +1908,19 @@ mod test {
         assert_lisp("(nreverse '(1 2))", "(2 1)");
         assert_lisp("(nreverse '(1 2 3))", "(3 2 1)");
         assert_lisp("(nreverse '(1 2 3 4))", "(4 3 2 1)");
+        assert_lisp("(nreverse [])", "[]");
+        assert_lisp("(nreverse \"\")", "\"\"");
+        assert_lisp("(let ((v (vector 1 2 3))) (nreverse v) v)", "[3 2 1]");
+        assert_lisp("(let ((v (vector 1 2 3 4))) (nreverse v) v)", "[4 3 2 1]");
+        assert_lisp("(nreverse \"abc\")", "\"cba\"");
+
+        assert_lisp("(reverse nil)", "nil");
+        assert_lisp("(reverse [])", "[]");
+        assert_lisp("(reverse \"\")", "\"\"");
+        assert_lisp("(reverse '(1 2 3))", "(3 2 1)");
+        assert_lisp("(let ((v [1 2 3])) (list (reverse v) v))", "([3 2 1] [1 2 3])");
+        assert_lisp("(reverse \"aé😀\")", "\"😀éa\"");
+        assert_lisp("(condition-case nil (reverse 1) (error 'err))", "err");
     }
 
     #[test]
@@ -998,6 +1931,115 @@ mod test {
         assert_lisp("(nconc '(1 2) '(3 4) '(5 6))", "(1 2 3 4 5 6)");
         assert_lisp("(nconc nil '(1 2))", "(1 2)");
         assert_lisp("(nconc '(1 2) nil)", "(1 2)");
+        assert_lisp("(nconc (list 1) nil (list 2))", "(1 2)");
+        assert_lisp("(nconc nil (list 1) nil nil (list 2 3) nil)", "(1 2 3)");
+        assert_lisp("(nconc nil nil)", "nil");
+        assert_lisp("(nconc (list 1) '(2 . 3))", "(1 2 . 3)");
+        assert_lisp("(condition-case nil (nconc (cons 1 2) (list 3)) (error 'err))", "err");
+        assert_lisp("(condition-case nil (nconc (list 1) (cons 2 3) nil) (error 'err))", "err");
+    }
+
+    #[test]
+    fn test_plist_get() {
+        assert_lisp("(plist-get '(a 1 b 2) 'b)", "2");
+        assert_lisp("(plist-get '(a 1 b 2) 'c)", "nil");
+        assert_lisp("(plist-get nil 'a)", "nil");
+        assert_lisp("(plist-get 5 'a)", "nil");
+        // malformed plists don't signal an error
+        assert_lisp("(plist-get '(a 1 b) 'b)", "nil");
+        assert_lisp("(plist-get '(a 1 b 2 . c) 'b)", "2");
+        assert_lisp("(plist-get '(a 1 b . 2) 'b)", "nil");
+        assert_lisp("(plist-get '(a 1 b 2 . c) 'c)", "nil");
+        assert_lisp(
+            "(let ((l (list 'a 1 'b 2))) (setcdr (cdr (cdr (cdr l))) l) (plist-get l 'z))",
+            "nil",
+        );
+    }
+
+    #[test]
+    fn test_mapcar() {
+        assert_lisp("(mapcar #'1+ nil)", "nil");
+        assert_lisp("(mapcar #'1+ '(1 2 3))", "(2 3 4)");
+        assert_lisp("(mapcar #'identity '(a (b) \"c\"))", "(a (b) \"c\")");
+        // collect garbage while the result list is being built
+        let long_list = "(let ((l nil) (i 0)) (while (< i 300) (setq l (cons i l) i (1+ i))) l)";
+        let mapped = format!(
+            "(mapcar #'(lambda (x) (if (= 0 (% x 100)) (garbage-collect)) (list x (* x 2))) {long_list})"
+        );
+        assert_lisp(
+            &format!(
+                "(let ((m {mapped})) (list (length m) (car m) (nth 299 m) (apply #'+ (mapcar #'(lambda (x) (nth 1 x)) m))))"
+            ),
+            "(300 (299 598) (0 0) 89700)",
+        );
+    }
+
+    #[test]
+    fn test_cl_map() {
+        assert_lisp("(cl-map 'list #'1+ '(1 2 3))", "(2 3 4)");
+        assert_lisp("(cl-map 'list #'1+ [1 2 3])", "(2 3 4)");
+        assert_lisp("(cl-map 'vector #'1+ '(1 2 3))", "[2 3 4]");
+        assert_lisp("(cl-map 'vector #'identity \"ab\")", "[97 98]");
+        assert_lisp("(cl-map 'string #'1+ \"ab\")", "\"bc\"");
+        assert_lisp("(cl-map nil #'1+ '(1 2 3))", "nil");
+        assert_lisp("(cl-map 'list #'1+ nil)", "nil");
+        // collect garbage while the results are being gathered
+        let map = |kind| {
+            format!("(cl-map '{kind} #'(lambda (x) (garbage-collect) (string (1+ x))) \"abc\")")
+        };
+        assert_lisp(&map("list"), r#"("b" "c" "d")"#);
+        assert_lisp(&map("vector"), r#"["b" "c" "d"]"#);
+    }
+
+    #[test]
+    fn test_cl_mapcar() {
+        assert_lisp("(cl-mapcar #'+ '(1 2 3) '(10 20))", "(11 22)");
+        assert_lisp("(cl-mapcar #'cons '(a b) '(1 2 3 4))", "((a . 1) (b . 2))");
+        assert_lisp("(cl-mapcar #'list '(1 2) [a b] \"xy\")", "((1 a 120) (2 b 121))");
+        assert_lisp("(cl-mapcar #'1+ '(1 2 3))", "(2 3 4)");
+        assert_lisp("(cl-mapcar #'+ '(1 2) nil)", "nil");
+        // collect garbage while mapping
+        assert_lisp(
+            "(cl-mapcar #'(lambda (x y) (garbage-collect) (list x y)) '(1 2 3) '(a b c))",
+            "((1 a) (2 b) (3 c))",
+        );
+    }
+
+    #[test]
+    fn test_cl_pairlis() {
+        assert_lisp("(cl-pairlis '(a b c) '(1 2 3))", "((a . 1) (b . 2) (c . 3))");
+        assert_lisp("(cl-pairlis '(a b c) '(1 2))", "((a . 1) (b . 2))");
+        assert_lisp("(cl-pairlis '(a) '(1 2))", "((a . 1))");
+        assert_lisp("(cl-pairlis '(a b) '(1 2) '((c . 3)))", "((a . 1) (b . 2) (c . 3))");
+        assert_lisp("(cl-pairlis nil '(1 2) '((c . 3)))", "((c . 3))");
+        assert_lisp("(cl-pairlis nil nil)", "nil");
+        assert_lisp("(let ((old '((c . 3)))) (eq (cdr (cl-pairlis '(a) '(1) old)) old))", "t");
+        assert_lisp("(cdr (assq 'b (cl-pairlis '(a b) '(1 (2)))))", "(2)");
+    }
+
+    #[test]
+    fn test_list_builder() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        root!(list, new(ListBuilder), cx);
+        assert_eq!(list.head(cx), crate::core::object::NIL);
+        for i in 0..1000 {
+            // Only the builder keeps the new elements alive
+            let elem = Cons::new(i, i * 2, cx);
+            list.push(elem.into(), cx).unwrap();
+            if i % 100 == 0 {
+                cx.garbage_collect(true);
+            }
+        }
+        cx.garbage_collect(true);
+        let head = list.head(cx);
+        let elements: Vec<_> = head.as_list().unwrap().map(|x| x.unwrap()).collect();
+        assert_eq!(elements.len(), 1000);
+        for (i, elem) in (0..).zip(elements) {
+            let cons: &Cons = elem.try_into().unwrap();
+            assert_eq!(cons.car(), i);
+            assert_eq!(cons.cdr(), i * 2);
+        }
     }
 
     #[test]
@@ -1011,6 +2053,18 @@ mod test {
         assert_lisp("(assq 6 '((1 . 2) (3 . 4) (5 . 6)))", "nil");
     }
 
+    #[test]
+    fn test_assq_value() {
+        assert_lisp("(assq-value 3 '((1 . 2) (3 . 4) (5 . 6)))", "4");
+        assert_lisp("(assq-value 'b '((a 1) (b 2 3)))", "(2 3)");
+        assert_lisp("(assq-value 6 '((1 . 2) (3 . 4) (5 . 6)))", "nil");
+        assert_lisp("(assq-value 'a nil)", "nil");
+        assert_lisp("(rassq-value 4 '((1 . 2) (3 . 4) (5 . 6)))", "3");
+        assert_lisp("(rassq-value 'x '((a . y) (b . x)))", "b");
+        assert_lisp("(rassq-value 3 '((1 . 2) (3 . 4) (5 . 6)))", "nil");
+        assert_lisp("(rassq-value 'a nil)", "nil");
+    }
+
     #[test]
     fn test_string_equal() {
         assert_lisp("(string-equal \"hello\" \"hello\")", "t");
@@ -1161,6 +2215,127 @@ mod test {
         assert_lisp("(let ((h (make-hash-table))) (puthash 1 6 h) (puthash 2 8 h) (puthash 3 10 h) (maphash 'eq h))", "nil");
     }
 
+    #[test]
+    fn test_copy_hash_table() {
+        let table =
+            "(let ((h (make-hash-table :test 'equal))) (puthash \"a\" 1 h) (puthash 'b 2 h) h)";
+        assert_lisp(
+            &format!(
+                "(let* ((h {table}) (c (copy-hash-table h)))
+                   (puthash \"a\" 10 c) (puthash 'new 3 c) (remhash 'b c)
+                   (list (gethash \"a\" h) (gethash 'b h) (gethash 'new h)
+                         (gethash \"a\" c) (gethash 'b c) (gethash 'new c)))"
+            ),
+            "(1 2 nil 10 nil 3)",
+        );
+        assert_lisp(
+            &format!("(let ((c (copy-hash-table {table}))) (list (hash-table-test c) (gethash \"a\" c)))"),
+            "(equal 1)",
+        );
+        assert_lisp(
+            "(let* ((h (make-hash-table)) (c (copy-hash-table h))) (puthash 1 1 c) (list (gethash 1 h) (eq h c)))",
+            "(nil nil)",
+        );
+    }
+
+    #[test]
+    fn test_hash_table_alist() {
+        let table = "(let ((h (make-hash-table :test 'equal))) (puthash \"a\" 1 h) (puthash '(b) 2 h) (puthash 3 'c h) h)";
+        let round_trip = format!(
+            "(let* ((h {table}) (c (alist-to-hash-table (hash-table-to-alist h) 'equal)))
+               (list (hash-table-test c)
+                     (length (hash-table-to-alist c))
+                     (gethash \"a\" c) (gethash (list 'b) c) (gethash 3 c)))"
+        );
+        assert_lisp(&round_trip, "(equal 3 1 2 c)");
+        assert_lisp(
+            &format!("(let ((alist (hash-table-to-alist {table}))) (list (assoc \"a\" alist) (assoc '(b) alist)))"),
+            "((\"a\" . 1) ((b) . 2))",
+        );
+        assert_lisp("(hash-table-to-alist (make-hash-table))", "nil");
+        assert_lisp("(hash-table-test (alist-to-hash-table nil))", "eql");
+        assert_lisp("(gethash 'a (alist-to-hash-table '((a . 1) (a . 2)) 'eq))", "1");
+        assert_lisp(
+            "(condition-case nil (alist-to-hash-table '((a . 1)) 'bad) (error 'invalid))",
+            "invalid",
+        );
+        assert_lisp("(condition-case nil (alist-to-hash-table '(a)) (error 'invalid))", "invalid");
+    }
+
+    #[test]
+    fn test_hash_table_size() {
+        // inserting up to the requested size does not grow the table
+        assert_lisp(
+            "(let* ((h (make-hash-table :size 100)) (size (hash-table-size h)) (i 0))
+               (while (< i 100) (puthash i i h) (setq i (1+ i)))
+               (list (>= size 100) (= size (hash-table-size h)) (gethash 99 h)))",
+            "(t t 99)",
+        );
+        assert_lisp("(hash-table-size (make-hash-table :size nil))", "0");
+        assert_lisp("(hash-table-size (make-hash-table))", "0");
+        assert_lisp(
+            "(let ((h (make-hash-table))) (puthash 1 1 h) (>= (hash-table-size h) 1))",
+            "t",
+        );
+        assert_lisp("(condition-case nil (make-hash-table :size -1) (error 'invalid))", "invalid");
+        assert_lisp(
+            "(condition-case nil (make-hash-table :size 1000000000000000) (error 'too-large))",
+            "too-large",
+        );
+        assert_lisp("(hash-table-rehash-size (make-hash-table))", "2.0");
+        assert_lisp("(hash-table-rehash-threshold (make-hash-table))", "1.0");
+    }
+
+    #[test]
+    fn test_hash_table_test() {
+        let table = |test| {
+            format!(
+                r#"(let ((h (make-hash-table :test '{test})) (a "key") (b (copy-sequence "key")))
+                     (puthash a 1 h) (puthash b 2 h) (list (gethash a h) (gethash b h)))"#
+            )
+        };
+        assert_lisp(&table("eq"), "(1 2)");
+        assert_lisp(&table("eql"), "(1 2)");
+        assert_lisp(&table("equal"), "(2 2)");
+        assert_lisp("(let ((h (make-hash-table :test 'equal))) (puthash '(1 [2 \"x\"]) 'a h) (gethash (list 1 (vector 2 \"x\")) h))", "a");
+        assert_lisp(
+            "(let ((h (make-hash-table :test 'eql))) (puthash 1.5 'a h) (gethash 1.5 h))",
+            "a",
+        );
+        assert_lisp(
+            "(let ((h (make-hash-table :test 'eq))) (puthash 'sym 'a h) (gethash 'sym h))",
+            "a",
+        );
+    }
+
+    #[test]
+    fn test_eq_hash_table() {
+        assert_lisp(
+            r#"(let ((h (make-hash-table :test 'eq)) (a (copy-sequence "str")) (b (copy-sequence "str")))
+                 (puthash a 1 h)
+                 (puthash b 2 h)
+                 (list (gethash a h) (gethash b h)))"#,
+            "(1 2)",
+        );
+        assert_lisp(
+            r#"(let ((h (make-hash-table :test 'eq)) (a (copy-sequence "str")))
+                 (puthash a 1 h)
+                 (gethash "str" h 'missing))"#,
+            "missing",
+        );
+        assert_lisp(
+            r#"(let ((h (make-hash-table :test 'eq)) (a (copy-sequence "str")) (b (copy-sequence "str")))
+                 (puthash a 1 h)
+                 (puthash b 2 h)
+                 (remhash a h)
+                 (list (gethash a h) (gethash b h)))"#,
+            "(nil 2)",
+        );
+        assert_lisp("(hash-table-test (make-hash-table :test 'eq))", "eq");
+        assert_lisp("(hash-table-test (make-hash-table :test 'equal))", "equal");
+        assert_lisp("(hash-table-test (make-hash-table))", "eql");
+    }
+
     #[test]
     fn test_sort() {
         assert_lisp("(sort nil '<)", "nil");
@@ -1181,4 +2356,145 @@ mod test {
     fn test_copy_alist() {
         assert_lisp("(copy-alist '((1 . 2) (3 . 4) (5 . 6)))", "((1 . 2) (3 . 4) (5 . 6))");
     }
+
+    #[test]
+    fn test_cl_subseq() {
+        assert_lisp("(cl-subseq '(1 2 3 4) 1 3)", "(2 3)");
+        assert_lisp("(cl-subseq '(1 2 3 4) 1)", "(2 3 4)");
+        assert_lisp("(cl-subseq '(1 2 3 4) 0 -1)", "(1 2 3)");
+        assert_lisp("(cl-subseq [1 2 3 4] 1 -1)", "[2 3]");
+        assert_lisp("(cl-subseq [1 2 3 4] -2)", "[3 4]");
+        assert_lisp(r#"(cl-subseq "hello" 1 3)"#, r#""el""#);
+        assert_lisp("(let* ((x '(1 2)) (y (cl-subseq x 0))) (eq x y))", "nil");
+        assert_lisp("(condition-case nil (cl-subseq '(1 2) 0 3) (error 'err))", "err");
+        assert_lisp("(condition-case nil (cl-subseq [1 2] 2 1) (error 'err))", "err");
+    }
+
+    #[test]
+    fn test_add_to_list() {
+        assert_lisp("(progn (setq x '(a b)) (add-to-list 'x 'c))", "(c a b)");
+        assert_lisp("(progn (setq x '(a b)) (add-to-list 'x 'c) x)", "(c a b)");
+        assert_lisp("(progn (setq x '(\"a\" b)) (add-to-list 'x \"a\") x)", "(\"a\" b)");
+        assert_lisp("(progn (setq x '(a b)) (add-to-list 'x 'c t) x)", "(a b c)");
+        assert_lisp("(progn (setq x nil) (add-to-list 'x 'a t) x)", "(a)");
+        assert_lisp("(progn (setq x '(1 2)) (add-to-list 'x 1.0 nil #'=) x)", "(1 2)");
+        assert_lisp("(progn (setq x '(1 2)) (add-to-list 'x 1.0 nil #'eq) x)", "(1.0 1 2)");
+        assert_lisp(
+            "(progn (setq x '((a . 1))) (add-to-list 'x 'a nil #'(lambda (elt member) (eq elt (car member)))) x)",
+            "((a . 1))",
+        );
+        assert_lisp(
+            "(progn (setq x '(1)) (condition-case nil (add-to-list 'x 2 nil #'(lambda (_elt _member) (makunbound 'x) nil)) (error 'void)))",
+            "void",
+        );
+    }
+
+    #[test]
+    fn test_cl_remove() {
+        assert_lisp("(cl-remove 1 '(1 2 1 3 1))", "(2 3)");
+        assert_lisp("(cl-remove 1 '(1 2 1 3 1) :count 2)", "(2 3 1)");
+        assert_lisp("(cl-remove 1 '(1 2 1) :count 0)", "(1 2 1)");
+        assert_lisp("(cl-remove 1 '(1 2 1) :count nil)", "(2)");
+        assert_lisp("(cl-remove 2 '(1 2 3 4) :test #'<)", "(1 2)");
+        assert_lisp("(cl-remove 2 '(1 2 3 4) :test #'< :count 1)", "(1 2 4)");
+        assert_lisp(r#"(cl-remove "a" '("a" "b" "a") :test #'equal)"#, r#"("b")"#);
+        assert_lisp("(cl-remove 'a '((a 1) (b 2) (a 3)) :key #'car)", "((b 2))");
+        assert_lisp("(cl-remove 1 [1 2 1 3])", "[2 3]");
+        assert_lisp(r#"(cl-remove ?a "banana" :count 2)"#, r#""bnna""#);
+        assert_lisp("(let ((l (list 1 2 1))) (cl-remove 1 l) l)", "(1 2 1)");
+        assert_lisp("(cl-remove 1 nil)", "nil");
+        assert_lisp("(condition-case nil (cl-remove 1 '(1) :bad 1) (error 'err))", "err");
+    }
+
+    #[test]
+    fn test_cl_substitute() {
+        assert_lisp("(cl-substitute 'x 1 '(1 2 1 3 1))", "(x 2 x 3 x)");
+        assert_lisp("(cl-substitute 'x 1 '(1 2 1 3 1) :count 2)", "(x 2 x 3 1)");
+        assert_lisp("(cl-substitute 'x 1 '(1 2 1) :count 0)", "(1 2 1)");
+        assert_lisp("(cl-substitute 'x 1 '(2 3))", "(2 3)");
+        assert_lisp("(cl-substitute 0 2 '(1 2 3 4) :test #'<)", "(1 2 0 0)");
+        assert_lisp(
+            r#"(cl-substitute "z" "a" '("a" "b" "a") :test #'equal :count 1)"#,
+            r#"("z" "b" "a")"#,
+        );
+        assert_lisp("(cl-substitute 0 1 [1 2 1])", "[0 2 0]");
+        assert_lisp(r#"(cl-substitute ?o ?a "banana")"#, r#""bonono""#);
+        assert_lisp("(let ((l (list 1 2))) (cl-substitute 0 1 l) l)", "(1 2)");
+        assert_lisp("(cl-substitute 0 1 nil)", "nil");
+    }
+
+    #[test]
+    fn test_cl_reduce() {
+        assert_lisp("(cl-reduce #'+ '(1 2 3 4))", "10");
+        // (((1 - 2) - 3) - 4) versus (1 - (2 - (3 - 4)))
+        assert_lisp("(cl-reduce #'- '(1 2 3 4))", "-8");
+        assert_lisp("(cl-reduce #'- '(1 2 3 4) :from-end t)", "-2");
+        assert_lisp("(cl-reduce #'list '(1 2 3))", "((1 2) 3)");
+        assert_lisp("(cl-reduce #'list '(1 2 3) :from-end t)", "(1 (2 3))");
+        assert_lisp("(cl-reduce #'list '(1 2) :initial-value 0)", "((0 1) 2)");
+        assert_lisp("(cl-reduce #'list '(1 2) :initial-value 0 :from-end t)", "(1 (2 0))");
+        assert_lisp("(cl-reduce #'- [10 1 2] :from-end nil)", "7");
+        assert_lisp("(cl-reduce #'+ '((a . 1) (b . 2)) :key #'cdr)", "3");
+        assert_lisp("(cl-reduce #'+ nil)", "0");
+        assert_lisp("(cl-reduce #'+ nil :initial-value 5)", "5");
+        assert_lisp("(cl-reduce #'list '(7))", "7");
+        assert_lisp("(cl-reduce #'list '(7) :from-end t)", "7");
+    }
+
+    #[test]
+    fn test_cl_delete() {
+        assert_lisp("(cl-delete 1 (list 1 2 1 3 1))", "(2 3)");
+        assert_lisp("(cl-delete 1 (list 1 1 2 1 3 1) :count 3)", "(2 3 1)");
+        assert_lisp("(cl-delete 1 (list 1 1))", "nil");
+        assert_lisp("(let ((l (list 0 1 2 1 3))) (cl-delete 1 l) l)", "(0 2 3)");
+        assert_lisp("(let ((l (list 0 1 2 1 3))) (cl-delete 1 l :count 1) l)", "(0 2 1 3)");
+        assert_lisp("(cl-delete 2 (list 1 2 3 4) :test #'<)", "(1 2)");
+        assert_lisp("(cl-delete 'a (list '(a 1) '(b 2)) :key #'car)", "((b 2))");
+        assert_lisp("(cl-delete 1 [1 2 1])", "[2]");
+    }
+
+    #[test]
+    fn test_cl_remove_duplicates() {
+        assert_lisp("(cl-remove-duplicates '(1 2 1 3 2))", "(1 3 2)");
+        assert_lisp("(cl-remove-duplicates '(1 2 1 3 2) :from-end t)", "(1 2 3)");
+        assert_lisp("(cl-remove-duplicates [a b a])", "[b a]");
+        assert_lisp(r#"(cl-remove-duplicates "abca" :from-end t)"#, r#""abc""#);
+        assert_lisp(r#"(cl-remove-duplicates '("a" "b" "a"))"#, r#"("a" "b" "a")"#);
+        assert_lisp(r#"(cl-remove-duplicates '("a" "b" "a") :test #'equal)"#, r#"("b" "a")"#);
+        assert_lisp("(cl-remove-duplicates '(1 2.0 2 3) :test #'=)", "(1 2 3)");
+        assert_lisp("(cl-remove-duplicates '(1 2.0 2 3) :test #'= :from-end t)", "(1 2.0 3)");
+        assert_lisp("(let ((x (list 1 1))) (cl-remove-duplicates x) x)", "(1 1)");
+    }
+
+    #[test]
+    fn test_cl_sort() {
+        assert_lisp("(let ((v (vector 3 1 2))) (cl-sort v #'<) v)", "[1 2 3]");
+        assert_lisp("(let ((v (vector 3 1 2))) (eq v (cl-sort v #'>)))", "t");
+        assert_lisp("(cl-sort '(3 1 2) #'<)", "(1 2 3)");
+        assert_lisp("(cl-sort nil #'<)", "nil");
+        assert_lisp(
+            "(cl-sort '((b . 2) (a . 1) (c . 0)) #'< :key #'cdr)",
+            "((c . 0) (a . 1) (b . 2))",
+        );
+        // equal keys keep their original order
+        assert_lisp(
+            "(cl-sort '((a . 1) (b . 0) (c . 1) (d . 0)) #'< :key #'cdr)",
+            "((b . 0) (d . 0) (a . 1) (c . 1))",
+        );
+        assert_lisp(
+            "(let ((v (vector \"bb\" \"a\" \"ccc\"))) (cl-sort v #'< :key #'length) v)",
+            r#"["a" "bb" "ccc"]"#,
+        );
+    }
+
+    #[test]
+    fn test_cl_find_if() {
+        assert_lisp("(cl-find-if #'(lambda (x) (> x 1)) '(1 2 3))", "2");
+        assert_lisp("(cl-find-if #'(lambda (x) (> x 5)) '(1 2 3))", "nil");
+        assert_lisp("(cl-find-if #'stringp nil)", "nil");
+        assert_lisp(
+            "(let ((calls 0)) (list (cl-find-if #'(lambda (x) (setq calls (1+ calls)) (> x 1)) '(1 2 3 4)) calls))",
+            "(2 2)",
+        );
+    }
 }
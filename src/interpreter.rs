@@ -2,7 +2,7 @@
 use crate::{
     core::{
         cons::{Cons, ElemStreamIter},
-        env::{sym, CallFrame, Env},
+        env::{intern, sym, CallFrame, Env},
         error::{ArgError, Type, TypeError},
         gc::{Context, Rt, Rto, Slot},
         object::{Function, Gc, List, ListType, Object, ObjectType, Symbol, TagType, NIL, TRUE},
@@ -15,12 +15,14 @@ use anyhow::Result as AnyResult;
 use anyhow::{bail, ensure};
 use fallible_iterator::FallibleIterator;
 use fallible_streaming_iterator::FallibleStreamingIterator;
-use rune_core::macros::{bail_err, call, error, rebind, root};
+use rune_core::macros::{bail_err, call, error, list, rebind, root};
 use rune_macros::defun;
 
 struct Interpreter<'brw, 'rt> {
     vars: &'brw mut Rt<Vec<Slot<&'rt Cons>>>,
     env: &'brw mut Rt<Env<'rt>>,
+    /// If false, `let` and lambda arguments are bound dynamically.
+    lexical: bool,
 }
 
 #[defun]
@@ -39,7 +41,21 @@ pub(crate) fn eval<'ob>(
             }
         }
     }
-    let mut interpreter = Interpreter { vars, env };
+    let mut interpreter = Interpreter { vars, env, lexical: lexical.is_some() };
+    interpreter.eval_form(form, cx).map_err(Into::into)
+}
+
+/// Evaluate `form` with an empty lexical environment. If `lexical_binding` is
+/// false, variables are bound dynamically like `(eval form nil)`.
+pub(crate) fn eval_toplevel<'ob>(
+    form: &Rto<Object>,
+    lexical_binding: bool,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>, anyhow::Error> {
+    cx.garbage_collect(false);
+    root!(vars, new(Vec<Slot<&Cons>>), cx);
+    let mut interpreter = Interpreter { vars, env, lexical: lexical_binding };
     interpreter.eval_form(form, cx).map_err(Into::into)
 }
 
@@ -72,8 +88,20 @@ impl Interpreter<'_, '_> {
                 sym::AND => self.eval_and(forms, cx),
                 sym::OR => self.eval_or(forms, cx),
                 sym::COND => self.eval_cond(forms, cx),
+                sym::CL_TYPECASE => self.eval_typecase(forms, false, cx),
+                sym::CL_ETYPECASE => self.eval_typecase(forms, true, cx),
+                sym::CL_CASE | sym::CASE => self.eval_case(forms, cx),
+                sym::CL_ASSERT => self.cl_assert(forms, cx),
+                sym::CL_CHECK_TYPE => self.cl_check_type(forms, cx),
+                sym::CL_BLOCK => self.cl_block(forms, cx),
+                sym::CL_RETURN => self.cl_return(forms, false, cx),
+                sym::CL_RETURN_FROM => self.cl_return(forms, true, cx),
+                sym::CL_DOTIMES => self.cl_dotimes(forms, cx),
+                sym::CL_DOLIST => self.cl_dolist(forms, cx),
+                sym::CL_MULTIPLE_VALUE_BIND => self.cl_multiple_value_bind(forms, cx),
                 sym::WHILE => self.eval_while(forms, cx),
-                sym::PROGN | sym::INLINE => self.eval_progn(forms, cx),
+                sym::PROGN | sym::INLINE | sym::EVAL_AND_COMPILE => self.eval_progn(forms, cx),
+                sym::EVAL_WHEN_COMPILE => self.eval_when_compile(forms, cx),
                 sym::PROG1 => self.eval_progx(forms, 1, cx),
                 sym::PROG2 => self.eval_progx(forms, 2, cx),
                 sym::SETQ => self.setq(forms, cx),
@@ -81,9 +109,10 @@ impl Interpreter<'_, '_> {
                 sym::FUNCTION => self.eval_function(forms, cx),
                 sym::INTERACTIVE => Ok(NIL), // TODO: implement
                 sym::CATCH => self.catch(forms, cx),
-                sym::THROW => self.throw(forms.bind(cx), cx),
                 sym::CONDITION_CASE => self.condition_case(forms, cx),
                 sym::SAVE_CURRENT_BUFFER => self.save_current_buffer(forms, cx),
+                sym::SAVE_MATCH_DATA => self.save_match_data(forms, cx),
+                sym::WITH_OUTPUT_TO_STRING => self.with_output_to_string(forms, cx),
                 sym::SAVE_EXCURSION => self.save_excursion(forms, cx),
                 sym::UNWIND_PROTECT => self.unwind_protect(forms, cx),
                 _ => {
@@ -98,6 +127,7 @@ impl Interpreter<'_, '_> {
     fn catch<'ob>(&mut self, obj: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
         rooted_iter!(forms, obj, cx);
         let Some(tag) = forms.next()? else { bail_err!(ArgError::new(1, 0, "catch")) };
+        let tag = rebind!(self.eval_form(tag, cx)?);
         // push this tag on the catch stack
         self.env.catch_stack.push(tag);
         let result = match self.implicit_progn(forms, cx) {
@@ -107,8 +137,10 @@ impl Interpreter<'_, '_> {
                     if let Some((throw_tag, data)) = self.env.get_exception(id) {
                         let catch_tag = self.env.catch_stack.last().unwrap();
                         // TODO: Remove binds
-                        if catch_tag == throw_tag {
-                            return Ok(data.bind(cx));
+                        if catch_tag.bind(cx).ptr_eq(throw_tag.bind(cx)) {
+                            let data = data.bind(cx);
+                            self.env.catch_stack.pop();
+                            return Ok(data);
                         }
                     }
                 }
@@ -120,23 +152,6 @@ impl Interpreter<'_, '_> {
         result
     }
 
-    fn throw<'ob>(&mut self, obj: Object, cx: &'ob Context) -> EvalResult<'ob> {
-        let mut forms = obj.as_list()?;
-        let len = forms.len()? as u16;
-        if len != 2 {
-            bail_err!(ArgError::new(2, len, "throw"));
-        }
-        let tag = forms.next().unwrap()?;
-        let value = forms.next().unwrap()?;
-        // Need to check now that there is a catch, because we may have a
-        // condition-case along the unwind path
-        if self.env.catch_stack.iter().any(|x| x.bind(cx) == tag) {
-            Err(EvalError::throw(tag, value, self.env))
-        } else {
-            Err(error!("No catch for {tag}"))
-        }
-    }
-
     fn defvar<'ob>(&mut self, obj: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
         rooted_iter!(forms, obj, cx);
         // (defvar x ...)                 // (defvar)
@@ -214,6 +229,11 @@ impl Interpreter<'_, '_> {
         let Ok((sym::LAMBDA, doc)) = form.bind(cx).as_cons_pair() else {
             return Ok(form.bind(cx));
         };
+        // Without lexical binding there is nothing to capture, so the lambda
+        // is its own function object.
+        if !self.lexical {
+            return Ok(form.bind(cx));
+        }
         root!(doc, doc.tag(), cx);
         let body = rebind!(self.replace_doc_symbol(doc, cx)?);
         let env = {
@@ -309,6 +329,18 @@ impl Interpreter<'_, '_> {
         self.implicit_progn(forms, cx)
     }
 
+    fn eval_when_compile<'ob>(
+        &mut self,
+        obj: &Rto<Object>,
+        cx: &'ob mut Context,
+    ) -> EvalResult<'ob> {
+        // There is no compiler, so the body is evaluated right away. Like at
+        // compile time, it can't see the enclosing lexical variables.
+        root!(vars, new(Vec<Slot<&Cons>>), cx);
+        let mut interpreter = Interpreter { vars, env: self.env, lexical: self.lexical };
+        interpreter.eval_progn(obj, cx)
+    }
+
     fn eval_while<'ob>(&mut self, obj: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
         let (condition, body) = {
             let list: List = obj.bind(cx).try_into()?;
@@ -344,6 +376,291 @@ impl Interpreter<'_, '_> {
         Ok(NIL)
     }
 
+    /// Run the body of the first clause whose type matches the key. If
+    /// `exhaustive` is true it is an error for no clause to match.
+    fn eval_typecase<'ob>(
+        &mut self,
+        obj: &Rto<Object>,
+        exhaustive: bool,
+        cx: &'ob mut Context,
+    ) -> EvalResult<'ob> {
+        rooted_iter!(forms, obj, cx);
+        let Some(key) = forms.next()? else { bail_err!(ArgError::new(1, 0, "cl-typecase")) };
+        let key = rebind!(self.eval_form(key, cx)?);
+        root!(key, cx);
+        while let Some(form) = forms.next()? {
+            rooted_iter!(clause, form, cx);
+            if let Some(ty) = clause.next()? {
+                if crate::data::cl_type_matches(key.bind(cx), ty.bind(cx), cx)? {
+                    return self.implicit_progn(clause, cx);
+                }
+            }
+        }
+        if exhaustive {
+            bail_err!("cl-etypecase failed: {key}");
+        }
+        Ok(NIL)
+    }
+
+    /// Run the body of the first clause whose key is `eql` to the value of the
+    /// key form. A clause key can be a list of keys, and `t` or `otherwise`
+    /// matches anything.
+    fn eval_case<'ob>(&mut self, obj: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
+        rooted_iter!(forms, obj, cx);
+        let Some(key) = forms.next()? else { bail_err!(ArgError::new(1, 0, "cl-case")) };
+        let key = rebind!(self.eval_form(key, cx)?);
+        root!(key, cx);
+        while let Some(form) = forms.next()? {
+            rooted_iter!(clause, form, cx);
+            let Some(keys) = clause.next()? else { continue };
+            let matches = match keys.bind(cx).untag() {
+                ObjectType::Symbol(sym::TRUE | sym::OTHERWISE) => true,
+                ObjectType::Cons(keys) => {
+                    keys.elements().fallible().any(|x| Ok(crate::fns::eql(x, key.bind(cx))))?
+                }
+                ObjectType::NIL => false,
+                _ => crate::fns::eql(keys.bind(cx), key.bind(cx)),
+            };
+            if matches {
+                return self.implicit_progn(clause, cx);
+            }
+        }
+        Ok(NIL)
+    }
+
+    /// Signal an error if the form evaluates to nil. The optional arguments
+    /// are `SHOW-ARGS`, which is ignored, and a format string and arguments
+    /// for the error message.
+    fn cl_assert<'ob>(&mut self, obj: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
+        rooted_iter!(forms, obj, cx);
+        let Some(form) = forms.next()? else { bail_err!(ArgError::new(1, 0, "cl-assert")) };
+        root!(form, cx);
+        if self.eval_form(form, cx)? != NIL {
+            return Ok(NIL);
+        }
+        forms.next()?;
+        let Some(string) = forms.next()? else {
+            let data = Cons::new1(form.bind(cx), cx);
+            return Err(EvalError::signal(sym::CL_ASSERTION_FAILED.into(), data.into(), self.env));
+        };
+        let string = rebind!(self.eval_form(string, cx)?);
+        root!(string, cx);
+        root!(args, new(Vec), cx);
+        while let Some(arg) = forms.next()? {
+            let arg = self.eval_form(arg, cx)?;
+            args.push(arg);
+        }
+        let string: &str = string.bind(cx).try_into()?;
+        let message = crate::editfns::format(string, Rt::bind_slice(args, cx))?;
+        let data = Cons::new1(cx.add(message), cx);
+        Err(EvalError::signal(sym::ERROR.into(), data.into(), self.env))
+    }
+
+    /// Signal a `wrong-type-argument` error unless the value of the place is
+    /// of the given type. An optional string describes the expected type in
+    /// the error.
+    fn cl_check_type<'ob>(&mut self, obj: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
+        rooted_iter!(forms, obj, cx);
+        let Some(place) = forms.next()? else { bail_err!(ArgError::new(2, 0, "cl-check-type")) };
+        root!(place, cx);
+        let Some(ty) = forms.next()? else { bail_err!(ArgError::new(2, 1, "cl-check-type")) };
+        root!(ty, cx);
+        let value = rebind!(self.eval_form(place, cx)?);
+        if crate::data::cl_type_matches(value, ty.bind(cx), cx)? {
+            return Ok(NIL);
+        }
+        root!(value, cx);
+        let expected = match forms.next()? {
+            Some(string) => rebind!(self.eval_form(string, cx)?),
+            None => ty.bind(cx),
+        };
+        let data = list![expected, value.bind(cx), place.bind(cx); cx];
+        Err(EvalError::signal(sym::WRONG_TYPE_ARGUMENT.into(), data, self.env))
+    }
+
+    fn cl_multiple_value_bind<'ob>(
+        &mut self,
+        obj: &Rto<Object>,
+        cx: &'ob mut Context,
+    ) -> EvalResult<'ob> {
+        const NAME: &str = "cl-multiple-value-bind";
+        rooted_iter!(forms, obj, cx);
+        let Some(vars) = forms.next()? else { bail_err!(ArgError::new(2, 0, NAME)) };
+        root!(vars, cx);
+        let Some(form) = forms.next()? else { bail_err!(ArgError::new(2, 1, NAME)) };
+        let values = rebind!(self.eval_form(form, cx)?);
+        // Multiple values are a list, and missing values are nil
+        let prev_len = self.vars.len();
+        let mut varbind_count = 0;
+        let mut values = values.as_list()?;
+        for var in vars.bind(cx).as_list()? {
+            let var: Symbol = var?.try_into()?;
+            let value = values.next().transpose()?.unwrap_or(NIL);
+            varbind_count += self.create_let_binding(var, value, cx);
+        }
+        let result = rebind!(self.implicit_progn(forms, cx)?);
+        self.vars.truncate(prev_len);
+        self.env.unbind(varbind_count, cx);
+        Ok(result)
+    }
+
+    fn cl_block<'ob>(&mut self, obj: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
+        rooted_iter!(forms, obj, cx);
+        let Some(name) = forms.next()? else { bail_err!(ArgError::new(1, 0, "cl-block")) };
+        let name: Symbol = name.bind(cx).try_into()?;
+        let prev_len = self.enter_block(name, cx);
+        let result = match self.implicit_progn(forms, cx) {
+            Ok(x) => Ok(rebind!(x, cx)),
+            Err(e) => Err(e),
+        };
+        self.exit_block(result, prev_len, cx)
+    }
+
+    /// Push the catch tag of the block `name`. Returns the number of lexical
+    /// variables so that [`Self::exit_block`] can drop the ones bound inside.
+    fn enter_block(&mut self, name: Symbol, cx: &Context) -> usize {
+        let tag: Object = block_tag(name, cx).into();
+        self.env.catch_stack.push(tag);
+        self.vars.len()
+    }
+
+    /// Pop the innermost block. If `result` is a `cl-return-from` that
+    /// targets it, the returned value becomes the value of the block.
+    fn exit_block<'ob>(
+        &mut self,
+        result: EvalResult<'ob>,
+        prev_len: usize,
+        cx: &'ob Context,
+    ) -> EvalResult<'ob> {
+        let result = match result {
+            Err(e) => match e.error {
+                ErrorType::Throw(id) => match self.env.get_exception(id) {
+                    Some((tag, data)) if self.env.catch_stack.last().unwrap() == tag => {
+                        self.vars.truncate(prev_len);
+                        Ok(data.bind(cx))
+                    }
+                    _ => Err(e),
+                },
+                _ => Err(e),
+            },
+            ok => ok,
+        };
+        self.env.catch_stack.pop();
+        result
+    }
+
+    fn cl_return<'ob>(
+        &mut self,
+        obj: &Rto<Object>,
+        named: bool,
+        cx: &'ob mut Context,
+    ) -> EvalResult<'ob> {
+        rooted_iter!(forms, obj, cx);
+        // (cl-return VALUE) is (cl-return-from nil VALUE)
+        let tag = if named {
+            let Some(name) = forms.next()? else {
+                bail_err!(ArgError::new(1, 0, "cl-return-from"))
+            };
+            block_tag(name.bind(cx).try_into()?, cx)
+        } else {
+            block_tag(sym::NIL, cx)
+        };
+        root!(tag, cx);
+        let value = match forms.next()? {
+            Some(value) => rebind!(self.eval_form(value, cx)?),
+            None => NIL,
+        };
+        let tag: Object = tag.bind(cx).into();
+        if self.env.catch_stack.iter().any(|x| x.bind(cx) == tag) {
+            Err(EvalError::throw(tag, value, self.env))
+        } else {
+            Err(error!("No catch for {tag}"))
+        }
+    }
+
+    fn cl_dotimes<'ob>(&mut self, obj: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
+        let (var, count, result, body) = loop_parts(obj.bind(cx), "cl-dotimes", cx)?;
+        root!(var, cx);
+        root!(count, cx);
+        root!(result, cx);
+        root!(body, cx);
+        let count: i64 = self.eval_form(count, cx)?.try_into()?;
+        let prev_len = self.enter_block(sym::NIL, cx);
+        let value = match self.dotimes_loop(var, count, result, body, cx) {
+            Ok(x) => Ok(rebind!(x, cx)),
+            Err(e) => Err(e),
+        };
+        self.exit_block(value, prev_len, cx)
+    }
+
+    fn dotimes_loop<'ob>(
+        &mut self,
+        var: &Rto<Symbol>,
+        count: i64,
+        result: &Rto<Object>,
+        body: &Rto<Object>,
+        cx: &'ob mut Context,
+    ) -> EvalResult<'ob> {
+        for i in 0..count {
+            root!(i, Object::from(i), cx);
+            self.loop_body(var, i, body, cx)?;
+        }
+        // RESULT sees the variable bound to COUNT
+        root!(count, Object::from(count), cx);
+        self.loop_body(var, count, result, cx)
+    }
+
+    fn cl_dolist<'ob>(&mut self, obj: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
+        let (var, list, result, body) = loop_parts(obj.bind(cx), "cl-dolist", cx)?;
+        root!(var, cx);
+        root!(result, cx);
+        root!(body, cx);
+        root!(list, cx);
+        let list = rebind!(self.eval_form(list, cx)?);
+        root!(list, cx);
+        let prev_len = self.enter_block(sym::NIL, cx);
+        let value = match self.dolist_loop(var, list, result, body, cx) {
+            Ok(x) => Ok(rebind!(x, cx)),
+            Err(e) => Err(e),
+        };
+        self.exit_block(value, prev_len, cx)
+    }
+
+    fn dolist_loop<'ob>(
+        &mut self,
+        var: &Rto<Symbol>,
+        list: &Rto<Object>,
+        result: &Rto<Object>,
+        body: &Rto<Object>,
+        cx: &'ob mut Context,
+    ) -> EvalResult<'ob> {
+        rooted_iter!(elements, list, cx);
+        while let Some(element) = elements.next()? {
+            root!(element, cx);
+            self.loop_body(var, element, body, cx)?;
+        }
+        // RESULT sees the variable bound to nil
+        root!(nil, NIL, cx);
+        self.loop_body(var, nil, result, cx)
+    }
+
+    /// Evaluate the list `forms` as an implicit progn with `var` bound to `value`.
+    fn loop_body<'ob>(
+        &mut self,
+        var: &Rto<Symbol>,
+        value: &Rto<Object>,
+        forms: &Rto<Object>,
+        cx: &'ob mut Context,
+    ) -> EvalResult<'ob> {
+        let prev_len = self.vars.len();
+        let varbind_count = self.create_let_binding(var.bind(cx), value.bind(cx), cx);
+        rooted_iter!(forms, forms, cx);
+        let value = rebind!(self.implicit_progn(forms, cx)?);
+        self.vars.truncate(prev_len);
+        self.env.unbind(varbind_count, cx);
+        Ok(value)
+    }
+
     fn eval_and<'ob>(&mut self, obj: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
         root!(last, TRUE, cx);
         rooted_iter!(forms, obj, cx);
@@ -529,7 +846,7 @@ impl Interpreter<'_, '_> {
     }
 
     fn create_let_binding(&mut self, var: Symbol, val: Object, cx: &Context) -> u16 {
-        if var.is_special() {
+        if var.is_special() || !self.lexical {
             self.env.varbind(var, val, cx);
             // return 1 if the variable is bound
             1
@@ -610,6 +927,46 @@ impl Interpreter<'_, '_> {
         Ok(result)
     }
 
+    /// Evaluate the body and then restore the match data, even if the body
+    /// exits non-locally.
+    fn save_match_data<'ob>(
+        &mut self,
+        form: &Rto<Object>,
+        cx: &'ob mut Context,
+    ) -> EvalResult<'ob> {
+        let saved = self.env.match_data.bind(cx);
+        root!(saved, cx);
+        let result = match self.eval_progn(form, cx) {
+            Ok(x) => Ok(rebind!(x, cx)),
+            Err(e) => Err(e),
+        };
+        self.env.match_data.set(saved.bind(cx));
+        result
+    }
+
+    /// Evaluate the body with `standard-output` bound to a temporary buffer,
+    /// and return the buffer's contents as a string.
+    fn with_output_to_string<'ob>(
+        &mut self,
+        form: &Rto<Object>,
+        cx: &'ob mut Context,
+    ) -> EvalResult<'ob> {
+        let name = crate::buffer::generate_new_buffer_name(" *string-output*", None);
+        let buffer = crate::buffer::get_buffer_create(cx.add(name), None, cx)?;
+        root!(buffer, cx);
+        self.env.varbind(sym::STANDARD_OUTPUT, buffer.bind(cx), cx);
+        let result = self.eval_progn(form, cx).map(|_| ());
+        self.env.unbind(1, cx);
+        let ObjectType::Buffer(buffer) = buffer.bind(cx).untag() else { unreachable!() };
+        let output = self.env.with_buffer_mut(buffer, |b| {
+            let text = b.text.to_string();
+            b.kill();
+            text
+        })?;
+        result?;
+        Ok(cx.add(output))
+    }
+
     fn condition_case<'ob>(&mut self, form: &Rto<Object>, cx: &'ob mut Context) -> EvalResult<'ob> {
         rooted_iter!(forms, form, cx);
         let Some(var) = forms.next()? else { bail_err!(ArgError::new(2, 0, "condition-case")) };
@@ -628,21 +985,8 @@ impl Interpreter<'_, '_> {
             match handler.untag(cx) {
                 ObjectType::Cons(cons) => {
                     // Check that conditions match
-                    let condition = cons.car();
-                    match condition.untag() {
-                        ObjectType::Symbol(sym::ERROR | sym::VOID_VARIABLE) => {}
-                        // TODO: Remove this once error handling is correctly implemented
-                        ObjectType::Symbol(s) if s.name() == "cl--generic-cyclic-definition" => {}
-                        ObjectType::Cons(conditions) => {
-                            for condition in conditions {
-                                let condition = condition?;
-                                // TODO: Handle different error symbols
-                                if condition != sym::DEBUG && condition != sym::ERROR {
-                                    bail_err!("non-error conditions {condition} not yet supported")
-                                }
-                            }
-                        }
-                        _ => bail_err!("Invalid condition handler: {condition}"),
+                    if !crate::eval::handler_matches(cons.car(), &err, self.env, cx)? {
+                        continue;
                     }
                     // Call handlers with error
                     let error = if let ErrorType::Signal(id) = err.error {
@@ -653,9 +997,9 @@ impl Interpreter<'_, '_> {
                     } else {
                         // TODO: Need to remove the anyhow branch once
                         // full errors are implemented
-                        Cons::new(sym::ERROR, format!("{err}"), cx)
+                        Cons::new(sym::ERROR, err.to_string().trim_end(), cx)
                     };
-                    let binding = Cons::new(var, Cons::new1(error, cx), cx);
+                    let binding = Cons::new(var, error, cx);
                     self.vars.push(binding);
                     let list: List = match cons.cdr().try_into() {
                         Ok(x) => x,
@@ -690,7 +1034,25 @@ pub(crate) fn call_closure<'ob>(
             let vars = bind_variables(&mut forms, args, name, cx)?;
             debug!("call vars: {vars:?}");
             root!(vars, cx);
-            Interpreter { vars, env }.implicit_progn(forms, cx)
+            Interpreter { vars, env, lexical: true }.implicit_progn(forms, cx)
+        }
+        ObjectType::Symbol(sym::LAMBDA) => {
+            // (lambda (x y) ...) is called with dynamic binding
+            rooted_iter!(forms, closure.cdr(), cx);
+            let Some(arg_list) = forms.next()? else { bail_err!("Lambda missing argument list") };
+            let args = Rt::bind_slice(&env.stack[..arg_cnt], cx);
+            let mut bindings = Vec::new();
+            bind_args(arg_list.bind(cx), args, &mut bindings, name, cx)?;
+            for binding in &bindings {
+                let var: Symbol = binding.car().try_into()?;
+                env.varbind(var, binding.cdr(), cx);
+            }
+            let varbind_count = bindings.len() as u16;
+            root!(vars, new(Vec<Slot<&Cons>>), cx);
+            let mut interpreter = Interpreter { vars, env, lexical: false };
+            let value = rebind!(interpreter.implicit_progn(forms, cx)?);
+            env.unbind(varbind_count, cx);
+            Ok(value)
         }
         other => Err(TypeError::new(Type::Func, other).into()),
     }
@@ -734,6 +1096,34 @@ fn parse_closure_env(obj: Object) -> AnyResult<Vec<&Cons>> {
     Ok(env)
 }
 
+/// The catch tag used by `cl-block` NAME and `cl-return-from` NAME.
+fn block_tag<'ob>(name: Symbol, cx: &'ob Context) -> Symbol<'ob> {
+    intern(&format!("--cl-block-{name}--"), cx)
+}
+
+/// Split a `cl-dolist` or `cl-dotimes` form `((VAR VALUE [RESULT]) BODY...)`
+/// into its parts.
+fn loop_parts<'ob>(
+    obj: Object<'ob>,
+    name: &str,
+    cx: &'ob Context,
+) -> Result<(Symbol<'ob>, Object<'ob>, Object<'ob>, Object<'ob>), EvalError> {
+    let ObjectType::Cons(cons) = obj.untag() else { bail_err!(ArgError::new(1, 0, name)) };
+    let mut spec = cons.car().as_list()?;
+    let len = spec.len()? as u16;
+    if !(2..=3).contains(&len) {
+        bail_err!(ArgError::new(2, len, name));
+    }
+    let var: Symbol = spec.next().unwrap()?.try_into()?;
+    let value = spec.next().unwrap()?;
+    // RESULT is kept as a list so it can be evaluated like the body
+    let result = match spec.next().transpose()? {
+        Some(result) => Cons::new1(result, cx).into(),
+        None => NIL,
+    };
+    Ok((var, value, result, cons.cdr()))
+}
+
 fn bind_args<'a>(
     arg_list: Object,
     args: &[Object<'a>],
@@ -814,7 +1204,7 @@ pub(crate) fn assert_lisp(compare: &str, expect: &str) {
     let compare = {
         let obj = crate::reader::read(compare, cx).unwrap().0;
         root!(obj, cx);
-        rebind!(eval(obj, None, env, cx).unwrap())
+        rebind!(eval_toplevel(obj, true, env, cx).unwrap())
     };
     let expect = crate::reader::read(expect, cx).unwrap().0;
     assert_eq!(compare, expect);
@@ -836,7 +1226,7 @@ mod test {
         println!("Test String: {test_str}");
         let obj = crate::reader::read(test_str, cx).unwrap().0;
         root!(obj, cx);
-        let compare = rebind!(eval(obj, None, env, cx).unwrap());
+        let compare = rebind!(eval_toplevel(obj, true, env, cx).unwrap());
         let expect: Object = expect.into_obj(cx).copy_as_obj(cx);
         assert_eq!(compare, expect);
     }
@@ -846,7 +1236,7 @@ mod test {
         println!("Test String: {test_str}");
         let obj = crate::reader::read(test_str, cx).unwrap().0;
         root!(obj, cx);
-        assert!(eval(obj, None, env, cx).is_err());
+        assert!(eval_toplevel(obj, true, env, cx).is_err());
     }
 
     #[test]
@@ -863,6 +1253,33 @@ mod test {
         check_interpreter("'(1 2)", list, cx);
     }
 
+    #[test]
+    fn test_function_quote() {
+        // `function' on a symbol is just `quote'
+        assert_lisp("#'car", "car");
+        assert_lisp("(eq #'car 'car)", "t");
+        assert_lisp("(funcall #'car '(1 2))", "1");
+        // On a lambda it makes a closure, while `quote' leaves the list alone
+        assert_lisp("(car #'(lambda () 1))", "closure");
+        assert_lisp("(car '(lambda () 1))", "lambda");
+        assert_lisp("(let ((x 1)) (car (cdr #'(lambda () x))))", "((x . 1) t)");
+        assert_lisp("(funcall #'(lambda () 1))", "1");
+    }
+
+    #[test]
+    fn test_vector_literal() {
+        assert_lisp("'[1 2]", "[1 2]");
+        assert_lisp("[1 2]", "[1 2]");
+        // The elements of a vector literal are not evaluated
+        assert_lisp("[a (b c)]", "[a (b c)]");
+        assert_lisp("(equal [1 (2 3)] '[1 (2 3)])", "t");
+        // Each evaluation returns the literal itself, not a copy
+        assert_lisp("(let ((f #'(lambda () [1 2]))) (eq (funcall f) (funcall f)))", "t");
+        assert_lisp("(let ((f #'(lambda () '[1 2]))) (eq (funcall f) (funcall f)))", "t");
+        // Equal literals are still distinct objects
+        assert_lisp("(eq [1 2] [1 2])", "nil");
+    }
+
     #[test]
     fn variables() {
         let roots = &RootSet::default();
@@ -911,6 +1328,40 @@ mod test {
         );
     }
 
+    #[test]
+    fn uninterned_symbols() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        // value cells are independent of the interned symbol
+        let list = list![2, 1; cx];
+        root!(list, cx);
+        check_interpreter(
+            "(progn (setq unint-test1 1) (let ((s (make-symbol \"unint-test1\"))) (set s 2) (list (symbol-value s) unint-test1)))",
+            list,
+            cx,
+        );
+        // function cells are independent of the interned symbol
+        let list = list![2, 1; cx];
+        root!(list, cx);
+        check_interpreter(
+            "(progn (defalias 'unint-test2 #'(lambda () 1)) (let ((s (make-symbol \"unint-test2\"))) (fset s #'(lambda () 2)) (list (funcall s) (unint-test2))))",
+            list,
+            cx,
+        );
+        // usable as let and setq targets
+        check_interpreter(
+            "(progn (setq unint-test3 1) (let ((s (make-symbol \"unint-test3\"))) (eval (list 'let (list (list s 5)) (list 'setq s (list '1+ s)) s))))",
+            6,
+            cx,
+        );
+        check_interpreter(
+            "(progn (setq unint-test4 1) (let ((s (make-symbol \"unint-test4\"))) (eval (list 'let (list (list s 5)) 'unint-test4))))",
+            1,
+            cx,
+        );
+        check_interpreter("(fboundp (make-symbol \"car\"))", false, cx);
+    }
+
     #[test]
     fn conditionals() {
         let roots = &RootSet::default();
@@ -1071,6 +1522,7 @@ mod test {
         check_interpreter("(condition-case nil (if) (error 7 9 11))", 11, cx);
         check_interpreter("(condition-case nil (if) (error . 7))", false, cx);
         check_interpreter("(condition-case nil (if) ((debug error) 7))", 7, cx);
+        check_interpreter("(condition-case err (signal 'error '(1 2)) (error (nth 1 err)))", 1, cx);
         check_error("(condition-case nil (if))", cx);
         check_error("(condition-case nil (if) nil)", cx);
         check_error("(condition-case nil (if) 5 (error 7))", cx);
@@ -1091,5 +1543,227 @@ mod test {
         check_interpreter("(catch 1 (catch 2 (throw 1 3)))", 3, cx);
         check_error("(throw 1 2)", cx);
         check_error("(catch 2 (throw 3 4))", cx);
+        // the tag and value are evaluated
+        check_interpreter("(catch (+ 1 1) (throw 2 (+ 3 4)))", 7, cx);
+        check_interpreter("(let ((tag 'b)) (catch tag (throw 'b 5)))", 5, cx);
+        check_interpreter("(catch (intern \"c\") (funcall #'(lambda () (throw 'c 6))))", 6, cx);
+        check_error("(catch 'a (throw 'b 1))", cx);
+        // equal but distinct tags do not match
+        check_error("(catch (copy-sequence \"a\") (throw (copy-sequence \"a\") 1))", cx);
+        check_error("(catch (list 1) (throw (list 1) 2))", cx);
+        check_error("(catch 1.5 (throw (+ 1.0 0.5) 3))", cx);
+        check_interpreter("(let ((tag (list 1))) (catch tag (throw tag 4)))", 4, cx);
+        // the tag is removed once the catch is done
+        check_error("(progn (catch 1 (throw 1 2)) (throw 1 3))", cx);
+    }
+
+    #[test]
+    fn test_dynamic_binding() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        let shadow = "(let ((x 1)) (let ((f #'(lambda () x))) (let ((x 2)) (funcall f))))";
+        check_interpreter(&format!("(eval '{shadow} t)"), 1, cx);
+        check_interpreter(&format!("(eval '{shadow} nil)"), 2, cx);
+        check_interpreter("(eval '(let ((x 1)) (funcall #'(lambda (y) (+ x y)) 2)))", 3, cx);
+        check_interpreter(
+            "(progn (defalias 'dyn-get #'(lambda () dyn-x)) (eval '(let ((dyn-x 5)) (dyn-get))))",
+            5,
+            cx,
+        );
+        check_error(
+            "(progn (defalias 'lex-get #'(lambda () lex-x)) (let ((lex-x 5)) (lex-get)))",
+            cx,
+        );
+        check_interpreter("(eval '(let ((x 3)) (boundp 'x)) nil)", true, cx);
+        check_interpreter("(eval '(let ((x 3)) (boundp 'x)) t)", false, cx);
+    }
+
+    #[test]
+    fn test_subr_arg_index() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        sym::init_symbols();
+        root!(env, new(Env), cx);
+        for (test_str, arg) in
+            [("(aref [1] (quote a))", ", arg 1"), ("(make-string (quote a) ?b)", ", arg 0")]
+        {
+            let obj = crate::reader::read(test_str, cx).unwrap().0;
+            root!(obj, cx);
+            let err = eval_toplevel(obj, true, env, cx).unwrap_err();
+            assert!(err.to_string().trim_end().ends_with(arg), "{test_str}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_typecase() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        let typecase =
+            |key| format!("(cl-typecase {key} (integer 1) ((or string symbol) 2) (cons) (t 3))");
+        check_interpreter(&typecase("(+ 1 2)"), 1, cx);
+        check_interpreter(&typecase("\"foo\""), 2, cx);
+        check_interpreter(&typecase("'(1)"), false, cx);
+        check_interpreter(&typecase("1.5"), 3, cx);
+        check_interpreter("(cl-typecase 1.5 (integer 1))", false, cx);
+        check_interpreter("(cl-typecase nil (null 1) (list 2))", 1, cx);
+        check_interpreter("(cl-typecase 3 ((member 1 2) 1) ((member 3) 2))", 2, cx);
+        check_interpreter("(cl-etypecase \"foo\" (integer 1) (string 2))", 2, cx);
+        check_error("(cl-etypecase 1.5 (integer 1) (string 2))", cx);
+        check_error("(cl-typecase 1 (not-a-type 1))", cx);
+    }
+
+    #[test]
+    fn test_case() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        check_interpreter("(cl-case (+ 1 1) (1 'one) (2 20) (t 3))", 20, cx);
+        check_interpreter("(cl-case 'b ((a b c) 1) (d 2))", 1, cx);
+        check_interpreter("(cl-case ?x (?y 1) ((?z ?x) 2))", 2, cx);
+        check_interpreter("(cl-case 5 (1 1) (2 2) (otherwise 3))", 3, cx);
+        check_interpreter("(cl-case 5 (1 1) (t 2 3))", 3, cx);
+        check_interpreter("(cl-case 5 (1 1) (2 2))", false, cx);
+        check_interpreter("(cl-case nil (nil 1) ((nil) 2))", 2, cx);
+        check_interpreter("(case 1.5 (1.5 1))", 1, cx);
+    }
+
+    #[test]
+    fn test_with_output_to_string() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        check_interpreter(r#"(with-output-to-string (princ "ab") (princ "cd"))"#, "abcd", cx);
+        check_interpreter(r#"(with-output-to-string (prin1 "ab") (princ 'cd))"#, r#""ab"cd"#, cx);
+        check_interpreter("(with-output-to-string (print 1) (terpri))", "\n1\n\n", cx);
+        check_interpreter("(with-output-to-string)", "", cx);
+        check_interpreter(
+            r#"(with-output-to-string (princ "a") (princ (with-output-to-string (princ "b"))))"#,
+            "ab",
+            cx,
+        );
+        check_interpreter(r#"(with-output-to-string (princ (prin1 "x")))"#, r#""x"x"#, cx);
+        check_error("(with-output-to-string (princ 1) (if))", cx);
+    }
+
+    #[test]
+    fn test_save_match_data() {
+        let outer = r#"(string-match "b\\(c\\)" "abcd")"#;
+        let inner = r#"(string-match "d" "xxd")"#;
+        assert_lisp(&format!("(progn {outer} (save-match-data {inner} (match-beginning 0)))"), "2");
+        assert_lisp(
+            &format!("(progn {outer} (save-match-data {inner}) (match-data))"),
+            "(1 3 2 3)",
+        );
+        assert_lisp(&format!("(progn {outer} (save-match-data {inner} 'done))"), "done");
+        assert_lisp("(save-match-data)", "nil");
+        // restored after a non-local exit
+        assert_lisp(
+            &format!(
+                "(progn {outer} (catch 1 (save-match-data {inner} (throw 1 2))) (match-data))"
+            ),
+            "(1 3 2 3)",
+        );
+        assert_lisp(
+            &format!(
+                "(progn {outer} (condition-case nil (save-match-data {inner} (car 1)) (error nil)) (match-end 0))"
+            ),
+            "3",
+        );
+    }
+
+    #[test]
+    fn test_cl_assert() {
+        assert_lisp("(cl-assert (= 1 1))", "nil");
+        assert_lisp("(cl-assert t nil \"unused\")", "nil");
+        assert_lisp(
+            "(condition-case err (cl-assert (= 1 2)) (cl-assertion-failed err))",
+            "(cl-assertion-failed (= 1 2))",
+        );
+        assert_lisp(
+            "(condition-case err (cl-assert nil nil \"bad %s\" (+ 1 2)) (error err))",
+            "(error \"bad 3\")",
+        );
+        assert_lisp("(let ((x 1)) (cl-check-type x integer))", "nil");
+        assert_lisp(
+            "(let ((x 1)) (condition-case err (cl-check-type x string) (wrong-type-argument err)))",
+            "(wrong-type-argument string 1 x)",
+        );
+        assert_lisp(
+            "(condition-case err (cl-check-type (car '(a)) number \"a number\") (error (cdr err)))",
+            "(\"a number\" a (car '(a)))",
+        );
+    }
+
+    #[test]
+    fn test_cl_loops() {
+        assert_lisp("(let ((sum 0)) (cl-dotimes (i 4 sum) (setq sum (+ sum i))))", "6");
+        assert_lisp("(cl-dotimes (i 3 i))", "3");
+        assert_lisp("(cl-dotimes (i 10) (if (= i 3) (cl-return (* i 10))))", "30");
+        assert_lisp("(cl-dotimes (i 10) (if (> i 20) (cl-return i)))", "nil");
+        assert_lisp(
+            "(let ((acc nil)) (cl-dolist (x '(1 2 3) (nreverse acc)) (setq acc (cons (* x x) acc))))",
+            "(1 4 9)",
+        );
+        assert_lisp("(cl-dolist (x '(a b c d)) (if (eq x 'c) (cl-return x)))", "c");
+        assert_lisp("(cl-dolist (x '(1 2) x))", "nil");
+        assert_lisp(
+            "(cl-block outer (cl-dolist (x '(1 2 3)) (cl-dotimes (i x) (if (= i 1) (cl-return-from outer (list x i))))))",
+            "(2 1)",
+        );
+        assert_lisp("(cl-block done 1 (cl-return-from done) 2)", "nil");
+        assert_lisp("(let ((x 'outer)) (cl-dotimes (x 5) (cl-return nil)) x)", "outer");
+        assert_lisp("(condition-case nil (cl-return 1) (error 'no-block))", "no-block");
+    }
+
+    #[test]
+    fn test_cl_dolist_independent() {
+        // sibling loops over the same variable
+        assert_lisp(
+            "(let ((acc nil)) (cl-dolist (x '(1 2)) (setq acc (cons x acc))) (cl-dolist (x '(a b c)) (setq acc (cons x acc))) (nreverse acc))",
+            "(1 2 a b c)",
+        );
+        // nested loops over the same variable
+        assert_lisp(
+            "(let ((acc nil)) (cl-dolist (x '(1 2)) (cl-dolist (x '(a b)) (setq acc (cons x acc))) (setq acc (cons x acc))) (nreverse acc))",
+            "(a b 1 a b 2)",
+        );
+        // nested loops over the same list
+        assert_lisp(
+            "(let ((l '(1 2 3)) (acc nil)) (cl-dolist (x l) (cl-dolist (y l) (setq acc (cons (list x y) acc)))) (length acc))",
+            "9",
+        );
+        // an inner loop exiting early does not affect the outer one
+        assert_lisp(
+            "(let ((acc nil)) (cl-dolist (x '(1 2 3)) (cl-dolist (y '(a b c)) (if (eq y 'b) (cl-return)) (setq acc (cons (list x y) acc)))) (nreverse acc))",
+            "((1 a) (2 a) (3 a))",
+        );
+        // each iteration gets its own binding
+        assert_lisp(
+            "(let ((fns nil)) (cl-dolist (x '(1 2)) (cl-dolist (y '(a b)) (setq fns (cons #'(lambda () (list x y)) fns)))) (mapcar #'funcall (nreverse fns)))",
+            "((1 a) (1 b) (2 a) (2 b))",
+        );
+    }
+
+    #[test]
+    fn test_eval_when_compile() {
+        assert_lisp("(eval-when-compile (+ 1 2) (* 3 4))", "12");
+        assert_lisp("(eval-when-compile)", "nil");
+        assert_lisp("(let ((x 2)) (+ x (eval-when-compile (* 10 10))))", "102");
+        assert_lisp("(let ((x 2)) (eval-and-compile (setq x (1+ x)) x))", "3");
+        // Only the global environment exists at compile time
+        assert_lisp("(progn (defvar ewc-var 5) (let ((x 1)) (eval-when-compile ewc-var)))", "5");
+        assert_lisp(
+            "(let ((x 1)) (condition-case nil (eval-when-compile x) (error 'unbound)))",
+            "unbound",
+        );
+    }
+
+    #[test]
+    fn test_multiple_value_bind() {
+        assert_lisp("(cl-values 1 2)", "(1 2)");
+        assert_lisp("(cl-values)", "nil");
+        assert_lisp("(cl-multiple-value-bind (q r) (cl-floor 17 5) (list q r))", "(3 2)");
+        assert_lisp("(cl-multiple-value-bind (q r) (cl-truncate -7 2) (+ (* q 2) r))", "-7");
+        assert_lisp("(cl-multiple-value-bind (a b c) (cl-values 1 2) (list a b c))", "(1 2 nil)");
+        assert_lisp("(cl-multiple-value-bind (a) (cl-values 1 2) a)", "1");
+        assert_lisp("(let ((a 'outer)) (cl-multiple-value-bind (a) (cl-values 1)) a)", "outer");
     }
 }
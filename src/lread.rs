@@ -15,6 +15,7 @@ use fallible_streaming_iterator::FallibleStreamingIterator;
 use rune_core::macros::{call, rebind, root};
 use rune_macros::defun;
 use std::fs;
+use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
 
 fn check_lower_bounds(idx: Option<i64>, len: usize) -> Result<usize> {
@@ -49,6 +50,11 @@ pub(crate) fn read_from_string<'ob>(
     let len = string.len();
     let start = check_lower_bounds(start, len)?;
     let end = check_upper_bounds(end, len)?;
+    // positions are byte offsets, so they must fall between characters
+    ensure!(
+        string.is_char_boundary(start) && string.is_char_boundary(end),
+        "index {start}..{end} is not on a character boundary in {string:?}"
+    );
 
     let (obj, new_pos) = match reader::read(&string[start..end], cx) {
         Ok((obj, pos)) => (obj, pos),
@@ -57,27 +63,126 @@ pub(crate) fn read_from_string<'ob>(
             bail!(e);
         }
     };
-    Ok(Cons::new(obj, new_pos as i64, cx).into())
+    Ok(Cons::new(obj, (start + new_pos) as i64, cx).into())
+}
+
+/// Read one form from `input`. `pending` holds text that has been read but
+/// not parsed yet: lines are added to it while the form is incomplete, and
+/// whatever follows the form is left in it for the next call. Returns `None`
+/// if the input ends before a form starts.
+pub(crate) fn read_from_input<'ob>(
+    input: &mut impl BufRead,
+    pending: &mut String,
+    cx: &'ob Context,
+) -> Result<Option<Object<'ob>>> {
+    use reader::Error as E;
+    let mut at_end = false;
+    loop {
+        match reader::read(pending, cx) {
+            Ok((obj, pos)) => {
+                pending.drain(..pos);
+                return Ok(Some(obj));
+            }
+            Err(E::EmptyStream) if at_end => {
+                pending.clear();
+                return Ok(None);
+            }
+            Err(
+                E::EmptyStream
+                | E::MissingCloseParen(_)
+                | E::MissingCloseBracket(_)
+                | E::MissingStringDel(_)
+                | E::MissingQuotedItem(_),
+            ) if !at_end => {}
+            Err(e) => {
+                pending.clear();
+                bail!(e);
+            }
+        }
+        at_end = input.read_line(pending)? == 0;
+    }
+}
+
+/// Read a line from `input` without the trailing newline.
+fn read_line_from(input: &mut impl BufRead) -> Result<String> {
+    let mut line = String::new();
+    ensure!(input.read_line(&mut line)? != 0, "Error reading from stdin");
+    if line.ends_with('\n') {
+        line.pop();
+    }
+    Ok(line)
+}
+
+#[defun]
+fn read<'ob>(stream: Option<Object<'ob>>, cx: &'ob Context) -> Result<Object<'ob>> {
+    match stream.map(|x| x.untag()) {
+        Some(ObjectType::String(string)) => Ok(reader::read(string, cx)?.0),
+        // In batch mode the standard input stream is stdin
+        None | Some(ObjectType::NIL | ObjectType::TRUE) => {
+            let obj = read_from_input(&mut io::stdin().lock(), &mut String::new(), cx)?;
+            obj.ok_or_else(|| anyhow!("End of file during parsing"))
+        }
+        Some(other) => bail!("Reading from {other} is not supported"),
+    }
+}
+
+#[defun]
+fn read_string(prompt: &str) -> Result<String> {
+    print!("{prompt}");
+    io::stdout().flush()?;
+    read_line_from(&mut io::stdin().lock())
+}
+
+/// Read each form from `input`, evaluate it, and print the result to
+/// `output`. Errors are printed and do not stop the loop.
+pub(crate) fn repl_over(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    env: &mut Rt<Env>,
+    cx: &mut Context,
+) -> Result<()> {
+    let mut pending = String::new();
+    loop {
+        let obj = match read_from_input(input, &mut pending, cx) {
+            Ok(Some(obj)) => obj,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                writeln!(output, "Error: {e}")?;
+                continue;
+            }
+        };
+        root!(obj, cx);
+        match interpreter::eval_toplevel(obj, true, env, cx) {
+            Ok(val) => writeln!(output, "{val}")?,
+            Err(e) => writeln!(output, "Error: {}", e.to_string().trim_end())?,
+        }
+    }
+}
+
+/// Evaluate the forms read from stdin and print their values, until the
+/// input ends.
+#[defun]
+fn batch_repl(env: &mut Rt<Env>, cx: &mut Context) -> Result<()> {
+    repl_over(&mut io::stdin().lock(), &mut io::stdout(), env, cx)
 }
 
 pub(crate) fn load_internal(contents: &str, cx: &mut Context, env: &mut Rt<Env>) -> Result<bool> {
-    let mut pos = 0;
     let macroexpand: Option<Function> = None;
     root!(macroexpand, cx);
     if let Some(fun) = sym::INTERNAL_MACROEXPAND_FOR_LOAD.func(cx) {
         macroexpand.set(Some(fun));
     }
+    let mut stream = reader::StreamReader::new(contents);
     loop {
-        let (obj, new_pos) = match reader::read(&contents[pos..], cx) {
-            Ok((obj, pos)) => (obj, pos),
+        let start = stream.pos();
+        let obj = match stream.read(cx) {
+            Ok(obj) => obj,
             Err(reader::Error::EmptyStream) => return Ok(true),
-            Err(mut e) => {
-                e.update_pos(pos);
-                bail!(e);
-            }
+            Err(e) => bail!(e),
         };
+        let end = stream.pos();
         if crate::debug::debug_enabled() {
-            let content = &contents[pos..(new_pos + pos)];
+            let content = &contents[start..end];
             println!("-----READ START-----\n {content}");
             println!("-----READ END-----");
         }
@@ -85,16 +190,15 @@ pub(crate) fn load_internal(contents: &str, cx: &mut Context, env: &mut Rt<Env>)
         let result = if let Some(fun) = macroexpand.as_ref() {
             eager_expand(obj, fun, env, cx)
         } else {
-            interpreter::eval(obj, None, env, cx)
+            interpreter::eval_toplevel(obj, lexical_binding(env, cx), env, cx)
         };
         if let Err(e) = result {
-            let content = &contents[pos..(new_pos + pos)];
+            let content = &contents[start..end];
             println!("-----LOAD ERROR START-----\n {content}");
             println!("-----LOAD ERROR END-----");
             return Err(e);
         }
-        assert_ne!(new_pos, 0);
-        pos += new_pos;
+        assert_ne!(start, end);
     }
 }
 
@@ -118,17 +222,27 @@ fn eager_expand<'ob>(
     }
     let result = call!(macroexpand, val, TRUE; name, env, cx)?;
     root!(result, cx);
-    interpreter::eval(result, None, env, cx)
+    interpreter::eval_toplevel(result, lexical_binding(env, cx), env, cx)
+}
+
+/// Whether forms being loaded should use lexical binding, based on the value
+/// of `lexical-binding'.
+fn lexical_binding(env: &Rt<Env>, cx: &Context) -> bool {
+    env.vars.get(sym::LEXICAL_BINDING).is_none_or(|x| !x.bind(cx).is_nil())
 }
 
+/// Find `file` in `path`. Like Emacs, compiled `.elc` files are preferred over
+/// `.el` source files, which are preferred over the file name as given.
 fn file_in_path(file: &str, path: &str) -> Option<PathBuf> {
     let path = Path::new(path).join(file);
-    if path.exists() {
-        Some(path)
-    } else {
-        let with_ext = path.with_extension("el");
-        with_ext.exists().then_some(with_ext)
-    }
+    let with_suffix = |suffix: &str| {
+        let mut name = path.clone().into_os_string();
+        name.push(suffix);
+        PathBuf::from(name)
+    };
+    [with_suffix(".elc"), with_suffix(".el"), path.clone()]
+        .into_iter()
+        .find(|x| x.is_file())
 }
 
 fn find_file_in_load_path(file: &str, cx: &Context, env: &Rt<Env>) -> Result<PathBuf> {
@@ -247,7 +361,8 @@ mod test {
 
     use super::*;
     use crate::core::gc::RootSet;
-    use rune_core::macros::root;
+    use rune_core::macros::{list, root};
+    use std::io::Cursor;
 
     #[test]
     #[allow(clippy::float_cmp)] // Bug in Clippy
@@ -260,7 +375,129 @@ mod test {
 
         let obj = reader::read("(+ foo bar baz)", cx).unwrap().0;
         root!(obj, cx);
-        let val = interpreter::eval(obj, None, env, cx).unwrap();
+        let val = interpreter::eval_toplevel(obj, true, env, cx).unwrap();
         assert_eq!(val, 4.5);
     }
+
+    #[test]
+    fn test_load_many_forms() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        sym::init_symbols();
+        root!(env, new(Env), cx);
+        let mut contents = String::from("(setq load-many-sum 0 load-many-items nil)\n");
+        for i in 0..1000 {
+            contents.push_str(&format!(
+                "(setq load-many-sum (+ load-many-sum {i})) ; form {i}\n(setq load-many-items (cons \"{i}\" load-many-items))\n"
+            ));
+        }
+        load_internal(&contents, cx, env).unwrap();
+
+        let obj =
+            reader::read("(list load-many-sum (length load-many-items) (car load-many-items))", cx)
+                .unwrap()
+                .0;
+        root!(obj, cx);
+        let val = interpreter::eval_toplevel(obj, true, env, cx).unwrap();
+        assert_eq!(val.to_string(), r#"(499500 1000 "999")"#);
+    }
+
+    #[test]
+    fn test_load_compiled() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        sym::init_symbols();
+        root!(env, new(Env), cx);
+        let dir = std::env::temp_dir().join(format!("rune-load-compiled-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = "(defalias 'load-compiled-test #'(lambda () 'source))";
+        fs::write(dir.join("load-compiled-test.el"), source).unwrap();
+        // "compile" a function that returns its first constant and write it out
+        let compiled = reader::read("(make-byte-code 0 (unibyte-string 192 135) [compiled] 1)", cx)
+            .unwrap()
+            .0;
+        root!(compiled, cx);
+        let compiled = interpreter::eval_toplevel(compiled, true, env, cx).unwrap();
+        let elc = format!("(defalias 'load-compiled-test {compiled})");
+        fs::write(dir.join("load-compiled-test.elc"), elc).unwrap();
+
+        let dir = dir.to_string_lossy();
+        let form = format!(
+            r#"(progn (setq load-path '("{dir}")) (load "load-compiled-test" nil t) (load-compiled-test))"#
+        );
+        let obj = reader::read(&form, cx).unwrap().0;
+        root!(obj, cx);
+        let val = interpreter::eval_toplevel(obj, true, env, cx).unwrap();
+        assert_eq!(val.to_string(), "compiled");
+        fs::remove_dir_all(&*dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_from_string_multibyte() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        let string = "(\"héllo\" . ü) 🦀 next";
+        let result = read_from_string(string, None, None, cx).unwrap();
+        let ObjectType::Cons(result) = result.untag() else { unreachable!() };
+        let pos = usize::try_from(result.cdr()).unwrap();
+        assert_eq!(&string[pos..], " 🦀 next");
+        // the returned position can be used as the start of the next read
+        let result = read_from_string(string, Some(pos as i64), None, cx).unwrap();
+        let ObjectType::Cons(result) = result.untag() else { unreachable!() };
+        assert_eq!(result.car(), intern("🦀", cx));
+        let pos = usize::try_from(result.cdr()).unwrap();
+        assert_eq!(&string[pos..], " next");
+        // an index inside a character is an error, not a panic
+        assert!(read_from_string(string, Some(4), None, cx).is_err());
+
+        let string = "(ü . 2 3)";
+        let err = read_from_string(string, None, None, cx).unwrap_err();
+        let err = err.downcast::<reader::Error>().unwrap();
+        assert_eq!(err, reader::Error::ExtraItemInCdr(string.find('3').unwrap()));
+        let string = format!("ü {string}");
+        let err = read_from_string(&string, Some(3), None, cx).unwrap_err();
+        let err = err.downcast::<reader::Error>().unwrap();
+        assert_eq!(err, reader::Error::ExtraItemInCdr(string.find('3').unwrap()));
+    }
+
+    #[test]
+    fn test_read_from_input() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        let mut input = Cursor::new("(1 2)\nfoo 3\n(a\n b)\n\n");
+        let pending = &mut String::new();
+        let mut next = || read_from_input(&mut input, pending, cx).unwrap();
+        assert_eq!(next().unwrap(), list![1, 2; cx]);
+        assert_eq!(next().unwrap(), intern("foo", cx));
+        assert_eq!(next().unwrap(), 3);
+        assert_eq!(next().unwrap(), list![intern("a", cx), intern("b", cx); cx]);
+        assert!(next().is_none());
+
+        let pending = &mut String::new();
+        assert!(read_from_input(&mut Cursor::new("(1"), pending, cx).is_err());
+        assert!(read_from_input(&mut Cursor::new(") 2"), pending, cx).is_err());
+        assert!(pending.is_empty());
+
+        let mut input = Cursor::new("hello world\nlast");
+        assert_eq!(read_line_from(&mut input).unwrap(), "hello world");
+        assert_eq!(read_line_from(&mut input).unwrap(), "last");
+        assert!(read_line_from(&mut input).is_err());
+    }
+
+    #[test]
+    fn test_repl_over() {
+        let roots = &RootSet::default();
+        let cx = &mut Context::new(roots);
+        sym::init_symbols();
+        root!(env, new(Env), cx);
+        let mut input = Cursor::new("(setq x 2)\n(+ x\n 1)\n(car 1) )\n(* x 10) \"str\"\n");
+        let mut output = Vec::new();
+        repl_over(&mut input, &mut output, env, cx).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines[..2], ["2", "3"]);
+        assert!(lines[2].starts_with("Error: expected List"));
+        assert!(lines[3].starts_with("Error: Extra Closing paren"));
+        assert_eq!(lines[4..], ["20", "\"str\""]);
+    }
 }
@@ -15,6 +15,7 @@ mod buffer;
 mod bytecode;
 mod casefiddle;
 mod character;
+mod chartab;
 mod data;
 mod dired;
 mod editfns;
@@ -28,6 +29,7 @@ mod interpreter;
 mod keymap;
 mod library;
 mod lread;
+mod marker;
 mod print;
 mod reader;
 mod search;
@@ -64,7 +66,7 @@ fn main() -> Result<(), ()> {
 
     sym::init_symbols();
     crate::core::env::init_variables(cx, env);
-    crate::data::defalias(intern("not", cx), (sym::NULL).into(), None)
+    crate::data::defalias(intern("not", cx), (sym::NULL).into(), None, env, cx)
         .expect("null should be defined");
 
     if !args.no_bootstrap {
@@ -93,7 +95,10 @@ fn repl(env: &mut Rt<Env>, cx: &mut Context) {
     loop {
         print!("> ");
         io::stdout().flush().unwrap();
-        stdin.read_line(&mut buffer).unwrap();
+        if stdin.read_line(&mut buffer).unwrap() == 0 {
+            // end of input
+            return;
+        }
         if buffer.trim() == "exit" {
             return;
         }
@@ -113,7 +118,7 @@ fn repl(env: &mut Rt<Env>, cx: &mut Context) {
         };
 
         root!(obj, cx);
-        match interpreter::eval(obj, None, env, cx) {
+        match interpreter::eval_toplevel(obj, true, env, cx) {
             Ok(val) => println!("{val}"),
             Err(e) => {
                 eprintln!("Error: {e}");
@@ -0,0 +1,58 @@
+//! Marker functions.
+use crate::core::{
+    error::{Type, TypeError},
+    object::{Marker, Object, ObjectType},
+};
+use anyhow::{bail, Result};
+use rune_macros::defun;
+
+/// Return the position of `marker`, or nil if it points nowhere.
+#[defun]
+fn marker_position(marker: &Marker) -> Option<usize> {
+    marker.position()
+}
+
+/// Position `marker` at `position`, which is an integer, another marker, or
+/// nil to make it point nowhere. Buffers are not supported yet so `buffer` is
+/// ignored.
+#[defun]
+fn set_marker<'ob>(
+    marker: Object<'ob>,
+    position: Object,
+    _buffer: Option<Object>,
+) -> Result<Object<'ob>> {
+    let ObjectType::Marker(m) = marker.untag() else {
+        bail!(TypeError::new(Type::Marker, marker))
+    };
+    let position = match position.untag() {
+        ObjectType::NIL => None,
+        ObjectType::Marker(other) => other.position(),
+        ObjectType::Int(x) => Some(usize::try_from(x).unwrap_or_default()),
+        _ => bail!(TypeError::new(Type::Int, position)),
+    };
+    m.set_position(position);
+    Ok(marker)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::interpreter::assert_lisp;
+
+    #[test]
+    fn test_marker() {
+        assert_lisp("(markerp (make-marker))", "t");
+        assert_lisp("(markerp 1)", "nil");
+        assert_lisp("(marker-position (make-marker))", "nil");
+        assert_lisp("(let ((m (make-marker))) (set-marker m 5) (marker-position m))", "5");
+        assert_lisp("(let ((m (make-marker))) (eq (set-marker m 5) m))", "t");
+        assert_lisp(
+            "(let ((m (make-marker))) (set-marker m 5) (set-marker m nil) (marker-position m))",
+            "nil",
+        );
+        assert_lisp(
+            "(let ((a (make-marker)) (b (make-marker))) (set-marker a 3) (set-marker b a) (set-marker a 4) (list (marker-position a) (marker-position b)))",
+            "(4 3)",
+        );
+        assert_lisp("(type-of (make-marker))", "marker");
+    }
+}
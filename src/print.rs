@@ -1,13 +1,208 @@
 //! Printing utilities.
-use crate::core::object::Object;
+use crate::core::{
+    env::{sym, Env},
+    gc::{Context, Rt, Rto},
+    object::{Function, Object, ObjectType, NIL, TRUE},
+};
+use anyhow::Result;
+use rune_core::macros::{call, root};
 use rune_macros::defun;
+use std::io::Write;
 
+/// Format an error object `(ERROR-SYMBOL . DATA)` the way it is reported to
+/// the user. The message comes from the `error-message' property of the error
+/// symbol, followed by the data. For `error' the message is the first element
+/// of the data instead.
 #[defun]
-fn error_message_string(obj: Object) -> String {
-    // TODO: implement
-    format!("Error: {obj}")
+fn error_message_string(obj: Object, env: &Rt<Env>, cx: &Context) -> Result<String> {
+    let ObjectType::Cons(error) = obj.untag() else { return Ok("peculiar error".to_owned()) };
+    // Errors that did not come from `signal' only have a message
+    if let ObjectType::String(msg) = error.cdr().untag() {
+        return Ok(msg.to_string());
+    }
+    let mut data: Vec<Object> = match error.cdr().untag() {
+        ObjectType::Cons(data) => data.elements().collect::<Result<_, _>>()?,
+        _ => Vec::new(),
+    };
+    let message = match error.car().untag() {
+        ObjectType::Symbol(sym::ERROR) if data.is_empty() => NIL,
+        ObjectType::Symbol(sym::ERROR) => data.remove(0),
+        ObjectType::Symbol(name) => crate::data::get(name, sym::ERROR_MESSAGE, env, cx),
+        _ => NIL,
+    };
+    let mut message = match message.untag() {
+        ObjectType::String(msg) => msg.to_string(),
+        _ => "peculiar error".to_owned(),
+    };
+    let quote = error.car() != sym::USER_ERROR;
+    for (i, item) in data.iter().enumerate() {
+        // an empty message has no separator before the first item
+        if i > 0 {
+            message.push_str(", ");
+        } else if !message.is_empty() {
+            message.push_str(": ");
+        }
+        match item.untag() {
+            ObjectType::String(string) if !quote => message.push_str(string),
+            _ => message.push_str(&item.to_string()),
+        }
+    }
+    Ok(message)
 }
 
+/// Send `text` to `printcharfun`, or to `standard-output` if it is nil. `t`
+/// prints to stdout, a buffer has the text inserted at point, and a function
+/// is called with each character.
+fn print_to(
+    text: &str,
+    printcharfun: Option<&Rto<Object>>,
+    env: &mut Rt<Env>,
+    cx: &mut Context,
+) -> Result<()> {
+    let stream = match printcharfun {
+        Some(stream) => stream.bind(cx),
+        None => env.vars.get(sym::STANDARD_OUTPUT).map_or(TRUE, |x| x.bind(cx)),
+    };
+    match stream.untag() {
+        ObjectType::NIL | ObjectType::TRUE => {
+            print!("{text}");
+            std::io::stdout().flush()?;
+        }
+        ObjectType::Buffer(buffer) => env.with_buffer_mut(buffer, |b| b.text.insert(text))?,
+        _ => {
+            root!(stream, cx);
+            let func: &Rto<Function> = stream.try_as()?;
+            for chr in text.chars() {
+                let chr: Object = (chr as i64).into();
+                call!(func, chr; env, cx)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[defun]
+fn prin1<'ob>(
+    object: &Rto<Object>,
+    printcharfun: Option<&Rto<Object>>,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let text = object.to_string();
+    print_to(&text, printcharfun, env, cx)?;
+    Ok(object.bind(cx))
+}
+
+#[defun]
+fn princ<'ob>(
+    object: &Rto<Object>,
+    printcharfun: Option<&Rto<Object>>,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let text = match object.bind(cx).untag() {
+        ObjectType::String(string) => string.to_string(),
+        _ => object.to_string(),
+    };
+    print_to(&text, printcharfun, env, cx)?;
+    Ok(object.bind(cx))
+}
+
+#[defun]
+fn print<'ob>(
+    object: &Rto<Object>,
+    printcharfun: Option<&Rto<Object>>,
+    env: &mut Rt<Env>,
+    cx: &'ob mut Context,
+) -> Result<Object<'ob>> {
+    let text = format!("\n{object}\n");
+    print_to(&text, printcharfun, env, cx)?;
+    Ok(object.bind(cx))
+}
+
+#[defun]
+fn terpri(
+    printcharfun: Option<&Rto<Object>>,
+    _ensure: Option<&Rto<Object>>,
+    env: &mut Rt<Env>,
+    cx: &mut Context,
+) -> Result<bool> {
+    print_to("\n", printcharfun, env, cx)?;
+    Ok(true)
+}
+
+defsym!(ERROR_MESSAGE);
+defsym!(USER_ERROR);
+defvar!(STANDARD_OUTPUT, true);
 defvar!(PRINT_LENGTH);
 defvar!(PRINT_LEVEL);
 defvar_bool!(PRINT_ESCAPE_NEWLINES, false);
+
+#[cfg(test)]
+mod test {
+    use crate::interpreter::assert_lisp;
+
+    #[test]
+    fn test_error_message_string() {
+        let message =
+            |form| format!("(condition-case err {form} (error (error-message-string err)))");
+        assert_lisp(&message(r#"(signal 'error '("Oops"))"#), r#""Oops""#);
+        assert_lisp(&message(r#"(signal 'error '("Oops" 1 "two"))"#), r#""Oops: 1, \"two\"""#);
+        assert_lisp(&message("(signal 'error nil)"), r#""peculiar error""#);
+        assert_lisp(
+            &message(
+                r#"(progn (put 'test-err 'error-message "Test failed") (signal 'test-err '(x 2)))"#,
+            ),
+            r#""Test failed: x, 2""#,
+        );
+        assert_lisp(&message("(signal 'unknown-err '(1))"), r#""peculiar error: 1""#);
+        assert_lisp(
+            &message(
+                r#"(progn (put 'user-error 'error-message "") (signal 'user-error '("a" "b")))"#,
+            ),
+            r#""a, b""#,
+        );
+        assert_lisp(r#"(error-message-string '(error . "Plain"))"#, r#""Plain""#);
+        assert_lisp("(error-message-string 5)", r#""peculiar error""#);
+        assert_lisp(&message("(car 1)"), r#""expected List, found Int: 1, arg 0""#);
+    }
+
+    #[test]
+    fn test_print_to_function() {
+        assert_lisp(
+            r#"(let ((chars nil))
+                 (princ "abc" #'(lambda (c) (setq chars (cons c chars))))
+                 (apply #'string (nreverse chars)))"#,
+            r#""abc""#,
+        );
+        assert_lisp(
+            r#"(let ((chars nil))
+                 (prin1 "ab" #'(lambda (c) (setq chars (cons c chars))))
+                 (apply #'string (nreverse chars)))"#,
+            r#""\"ab\"""#,
+        );
+        assert_lisp(
+            "(let ((chars nil))
+               (princ '(1 foo) #'(lambda (c) (setq chars (cons c chars))))
+               (apply #'string (nreverse chars)))",
+            r#""(1 foo)""#,
+        );
+        assert_lisp(
+            "(let ((chars nil))
+               (print 'x #'(lambda (c) (setq chars (cons c chars))))
+               (apply #'string (nreverse chars)))",
+            "\"\nx\n\"",
+        );
+        assert_lisp(
+            "(let ((chars nil))
+               (terpri #'(lambda (c) (setq chars (cons c chars))))
+               (apply #'string (nreverse chars)))",
+            "\"\n\"",
+        );
+        assert_lisp(r#"(prin1 "x" #'ignore)"#, r#""x""#);
+        assert_lisp(
+            r#"(with-output-to-string (princ "a" standard-output) (princ "b" #'ignore) (princ "c"))"#,
+            r#""ac""#,
+        );
+    }
+}
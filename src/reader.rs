@@ -2,7 +2,7 @@
 use crate::core::{
     env::{intern, sym},
     gc::Context,
-    object::{Object, Symbol},
+    object::{ByteFn, FnArgs, IntoObject, Object, ObjectType, Symbol},
 };
 use crate::fns;
 use rune_core::macros::list;
@@ -26,6 +26,7 @@ pub(crate) enum Error {
     UnknownMacroCharacter(char, usize),
     ParseInt(u8, usize),
     MalformedUnicdoe(usize),
+    InvalidByteCode(usize),
     EmptyStream,
 }
 
@@ -39,6 +40,7 @@ impl Display for Error {
             Error::ExtraCloseBracket(i) => write!(f, "Extra Closing brace: at {i}"),
             Error::UnexpectedChar(chr, i) => write!(f, "Unexpected character {chr}: at {i}"),
             Error::MalformedUnicdoe(i) => write!(f, "Malformed unicode: at {i}"),
+            Error::InvalidByteCode(i) => write!(f, "Invalid byte-code object: at {i}"),
             Error::EmptyStream => write!(f, "Empty Stream"),
             Error::ExtraItemInCdr(i) => write!(f, "Extra item in cdr: at {i}"),
             Error::MissingQuotedItem(i) => write!(f, "Missing element after quote: at {i}"),
@@ -66,6 +68,7 @@ impl Error {
             | Error::ExtraItemInCdr(x)
             | Error::UnexpectedChar(_, x)
             | Error::MalformedUnicdoe(x)
+            | Error::InvalidByteCode(x)
             | Error::ParseInt(_, x)
             | Error::UnknownMacroCharacter(_, x) => *x,
             Error::EmptyStream => 0,
@@ -79,6 +82,7 @@ impl Error {
             | Error::MissingStringDel(i)
             | Error::UnexpectedChar(_, i)
             | Error::MalformedUnicdoe(i)
+            | Error::InvalidByteCode(i)
             | Error::ExtraItemInCdr(i)
             | Error::ExtraCloseParen(i)
             | Error::ExtraCloseBracket(i)
@@ -330,13 +334,33 @@ fn intern_symbol<'ob>(symbol: &str, cx: &'ob Context) -> Symbol<'ob> {
 fn parse_symbol<'a>(slice: &str, cx: &'a Context) -> Object<'a> {
     match slice.parse::<i64>() {
         Ok(num) => cx.add(num),
-        Err(_) => match slice.parse::<f64>() {
-            Ok(num) => cx.add(num),
-            Err(_) => cx.add(intern_symbol(slice, cx)),
+        Err(_) => match parse_float(slice) {
+            Some(num) => cx.add(num),
+            None => cx.add(intern_symbol(slice, cx)),
         },
     }
 }
 
+/// Parse a float literal, including the special floats `0.0e+NaN` and
+/// `1.0e+INF`.
+fn parse_float(slice: &str) -> Option<f64> {
+    if let Some(mantissa) = slice.strip_suffix("e+NaN") {
+        let mantissa = parse_float(mantissa)?;
+        return Some(f64::NAN.copysign(mantissa));
+    }
+    if let Some(mantissa) = slice.strip_suffix("e+INF") {
+        let mantissa = parse_float(mantissa)?;
+        return Some(f64::INFINITY.copysign(mantissa));
+    }
+    // Rust also accepts words like `inf` and `NaN`, but those are symbols
+    let digits = slice.trim_start_matches(['+', '-']);
+    if digits.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        slice.parse().ok()
+    } else {
+        None
+    }
+}
+
 /// process escape characters in the string slice and return the resulting
 /// string.
 fn unescape_string<'a>(string: &str, cx: &'a Context) -> Object<'a> {
@@ -441,12 +465,20 @@ impl<'a, 'ob> Reader<'a, 'ob> {
         Err(Error::MissingCloseBracket(delim))
     }
 
+    /// Read the item following a quote character at `pos`. A closing delimiter
+    /// or the end of input means the quote has nothing to apply to.
+    fn read_quoted(&mut self, pos: usize) -> Result<Object<'ob>> {
+        match self.tokens.next() {
+            Some(Token::CloseParen(_) | Token::CloseBracket(_)) | None => {
+                Err(Error::MissingQuotedItem(pos))
+            }
+            Some(token) => self.read_sexp(token),
+        }
+    }
+
     /// Quote an item using `symbol`.
     fn quote_item(&mut self, pos: usize, symbol: Symbol) -> Result<Object<'ob>> {
-        let obj: Object = match self.tokens.next() {
-            Some(token) => self.read_sexp(token)?,
-            None => return Err(Error::MissingQuotedItem(pos)),
-        };
+        let obj = self.read_quoted(pos)?;
         Ok(list!(symbol, obj; self.cx))
     }
 
@@ -465,25 +497,58 @@ impl<'a, 'ob> Reader<'a, 'ob> {
     /// the future, but right now it just handles the special cases from elisp.
     fn read_sharp(&mut self, pos: usize) -> Result<Object<'ob>> {
         match self.tokens.read_char() {
-            Some('\'') => match self.tokens.next() {
-                Some(Token::OpenParen(i)) => {
-                    let list = self.read_list(i)?;
-                    Ok(list!(sym::FUNCTION, list; self.cx))
-                }
-                Some(token) => {
-                    let obj = self.read_sexp(token)?;
-                    Ok(list!(sym::FUNCTION, obj; self.cx))
-                }
-                None => Err(Error::MissingQuotedItem(pos)),
-            },
+            Some('\'') => self.quote_item(pos, sym::FUNCTION),
             Some('b') => self.read_radix(pos, 2),
             Some('o') => self.read_radix(pos, 8),
             Some('x') => self.read_radix(pos, 16),
+            Some('[') => self.read_byte_code(pos),
             Some(chr) => Err(Error::UnknownMacroCharacter(chr, pos)),
             None => Err(Error::MissingQuotedItem(pos)),
         }
     }
 
+    /// Read a byte-code function `#[ARGS CODE CONSTANTS DEPTH ...]`. This is
+    /// how byte-code functions are printed, so compiled code can be written to
+    /// a file and read back. CODE is either a unibyte string or a vector of
+    /// bytes.
+    fn read_byte_code(&mut self, pos: usize) -> Result<Object<'ob>> {
+        let elements = self.read_vec(pos)?;
+        let ObjectType::Vec(elements) = elements.untag() else { unreachable!() };
+        let invalid = Error::InvalidByteCode(pos);
+        let [args, code, constants, depth, ..] = &elements[..] else { return Err(invalid) };
+        let args = match args.get().untag() {
+            ObjectType::Int(spec) => FnArgs::from_arg_spec(spec as u64).map_err(|_| invalid)?,
+            _ => return Err(invalid),
+        };
+        let code: Vec<u8> = match code.get().untag() {
+            ObjectType::ByteString(code) => code.to_vec(),
+            ObjectType::String(code) => code
+                .chars()
+                .map(|c| u8::try_from(c).ok())
+                .collect::<Option<_>>()
+                .ok_or(invalid)?,
+            ObjectType::Vec(code) => code
+                .iter()
+                .map(|x| match x.get().untag() {
+                    ObjectType::Int(byte) => u8::try_from(byte).ok(),
+                    _ => None,
+                })
+                .collect::<Option<_>>()
+                .ok_or(invalid)?,
+            _ => return Err(invalid),
+        };
+        let ObjectType::Vec(constants) = constants.get().untag() else { return Err(invalid) };
+        crate::bytecode::verify(&code, &constants.to_vec(), args).map_err(|_| invalid)?;
+        let depth = match depth.get().untag() {
+            ObjectType::Int(depth) => usize::try_from(depth).map_err(|_| invalid)?,
+            _ => return Err(invalid),
+        };
+        // SAFETY: The constants were allocated in the same block, and the
+        // function is put in the heap right away.
+        let byte_fn = unsafe { ByteFn::make(&code, constants, args, depth) };
+        Ok(byte_fn.into_obj(self.cx).into())
+    }
+
     fn read_sexp(&mut self, token: Token<'a>) -> Result<Object<'ob>> {
         match token {
             Token::OpenParen(i) => self.read_list(i),
@@ -503,19 +568,46 @@ impl<'a, 'ob> Reader<'a, 'ob> {
     }
 }
 
+/// Reads successive objects from a string. The position is kept between reads,
+/// so the input does not have to be resliced and re-tokenized for each object.
+pub(crate) struct StreamReader<'a> {
+    tokens: Tokenizer<'a>,
+}
+
+impl<'a> StreamReader<'a> {
+    pub(crate) fn new(slice: &'a str) -> Self {
+        Self { tokens: Tokenizer::new(slice) }
+    }
+
+    /// Read the next object. Returns [`Error::EmptyStream`] once there are no
+    /// objects left. Error positions are relative to the start of the input.
+    pub(crate) fn read<'ob>(&mut self, cx: &'ob Context) -> Result<Object<'ob>> {
+        let mut reader = Reader { tokens: self.tokens.clone(), cx };
+        let result = match reader.tokens.next() {
+            Some(t) => reader.read_sexp(t),
+            None => Err(Error::EmptyStream),
+        };
+        self.tokens = reader.tokens;
+        result
+    }
+
+    /// The index of the next character to be read.
+    pub(crate) fn pos(&mut self) -> usize {
+        self.tokens.cur_pos()
+    }
+}
+
 /// read a lisp object from `slice`. Return the object and index of next
 /// remaining character in the slice.
 pub(crate) fn read<'ob>(slice: &str, cx: &'ob Context) -> Result<(Object<'ob>, usize)> {
-    let mut reader = Reader { tokens: Tokenizer::new(slice), cx };
-    match reader.tokens.next() {
-        Some(t) => reader.read_sexp(t).map(|x| (x, reader.tokens.cur_pos())),
-        None => Err(Error::EmptyStream),
-    }
+    let mut stream = StreamReader::new(slice);
+    let obj = stream.read(cx)?;
+    Ok((obj, stream.pos()))
 }
 
 #[cfg(test)]
 mod test {
-    use crate::core::{cons::Cons, gc::RootSet};
+    use crate::core::{cons::Cons, gc::RootSet, object::ObjectType};
 
     use super::*;
 
@@ -561,6 +653,26 @@ mod test {
         check_reader!(0xdead_beef_i64, "#xDeAdBeEf", cx);
     }
 
+    #[test]
+    fn test_read_special_float() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        check_reader!(f64::INFINITY, "1.0e+INF", cx);
+        check_reader!(f64::NEG_INFINITY, "-1.0e+INF", cx);
+        check_reader!(1500.0, "1.5e+3", cx);
+        // Printing and reading a special float gives back the same float
+        for printed in ["0.0e+NaN", "-0.0e+NaN", "1.0e+INF", "-1.0e+INF"] {
+            let obj = read(printed, cx).unwrap().0;
+            assert_eq!(obj.to_string(), printed);
+        }
+        let nan = read("0.0e+NaN", cx).unwrap().0;
+        assert!(matches!(nan.untag(), ObjectType::Float(f) if f.is_nan()));
+        // Words that Rust parses as floats are symbols in lisp
+        for name in ["inf", "-inf", "NaN", "infinity", "e+NaN"] {
+            check_reader!(intern(name, cx), name, cx);
+        }
+    }
+
     #[test]
     #[allow(clippy::non_ascii_literal)]
     fn test_read_char() {
@@ -599,6 +711,12 @@ mod test {
         check_reader!(intern("x.y", cx), "x.y", cx);
         check_reader!(intern("(* 1 2)", cx), "\\(*\\ 1\\ 2\\)", cx);
         check_reader!(intern("+-*/_~!@$%^&=:<>{}", cx), "+-*/_~!@$%^&=:<>{}", cx);
+        // escaped delimiters are part of the symbol name
+        check_reader!(intern("foo bar", cx), "foo\\ bar", cx);
+        check_reader!(list!(intern("foo bar", cx), intern("baz", cx); cx), "(foo\\ bar baz)", cx);
+        check_reader!(intern("f(o)o", cx), "f\\(o\\)o", cx);
+        check_reader!(intern("a;b'c\"d#e,f`g[h]", cx), "a\\;b\\'c\\\"d\\#e\\,f\\`g\\[h\\]", cx);
+        check_reader!(list!(intern(".", cx), intern("b", cx); cx), "(\\. b)", cx);
     }
 
     #[test]
@@ -632,6 +750,31 @@ baz""#,
         check_reader!(list!(1, 1.5, intern("...", cx), 2; cx), "(1 1.5 ... 2)", cx);
     }
 
+    #[test]
+    fn test_read_dotted_pair() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        let (a, b, c) = (intern("a", cx), intern("b", cx), intern("c", cx));
+        check_reader!(Cons::new(a, b, cx), "(a . b)", cx);
+        check_reader!(Cons::new(a, Cons::new(b, c, cx), cx), "(a b . c)", cx);
+        check_reader!(list!(a, b; cx), "(a . (b))", cx);
+        check_reader!(list!(a; cx), "(a . nil)", cx);
+        // a dot followed by symbol characters is part of the symbol
+        check_reader!(list!(a, intern(".b", cx); cx), "(a .b)", cx);
+        check_reader!(list!(intern("a.", cx), b; cx), "(a. b)", cx);
+        check_reader!(list!(a, 0.5; cx), "(a .5)", cx);
+        assert_error("(a . b c)", Error::ExtraItemInCdr(7), cx);
+        assert_error("(a . b", Error::MissingCloseParen(0), cx);
+
+        // printing and reading back should give an equal object
+        for input in ["(a . b)", "(a b . c)", "((a . b) . (c . nil))", "(a .b)", "(1 (2 . 3) . 4)"]
+        {
+            let obj = read(input, cx).unwrap().0;
+            let printed = obj.to_string();
+            assert_eq!(read(&printed, cx).unwrap().0, obj, "round trip of {input} via {printed}");
+        }
+    }
+
     #[test]
     fn read_quote() {
         let roots = &RootSet::default();
@@ -640,6 +783,20 @@ baz""#,
         check_reader!(list!(quote, sym::IF; cx), "(quote if)", cx);
         check_reader!(list!(quote, sym::IF; cx), "'if", cx);
         check_reader!(list!(quote, list!(1, 2, 3; cx); cx), "'(1 2 3)", cx);
+        check_reader!(list!(quote, list!(intern("a", cx), intern("b", cx); cx); cx), "'(a b)", cx);
+        check_reader!(list!(quote, list!(quote, sym::IF; cx); cx), "''if", cx);
+        check_reader!(list!(quote, list!(sym::UNQUOTE, sym::IF; cx); cx), "',if", cx);
+        check_reader!(list!(quote, list!(sym::SPLICE, sym::IF; cx); cx), "',@if", cx);
+        check_reader!(
+            list!(sym::BACKQUOTE, list!(intern("a", cx), list!(sym::UNQUOTE, list!(quote, intern("b", cx); cx); cx); cx); cx),
+            "`(a ,'b)",
+            cx
+        );
+        assert_error("'", Error::MissingQuotedItem(0), cx);
+        assert_error("(a ') ", Error::MissingQuotedItem(3), cx);
+        assert_error("[a ']", Error::MissingQuotedItem(3), cx);
+        assert_error("'  ; nothing follows\n", Error::MissingQuotedItem(0), cx);
+        assert_error("',", Error::MissingQuotedItem(1), cx);
         check_reader!(u32::from('a'), "?a", cx);
         check_reader!(u32::from(' '), "?\\s", cx);
         check_reader!(u32::from('\t'), "?\\t", cx);
@@ -661,6 +818,14 @@ baz""#,
         assert_error("#", Error::MissingQuotedItem(0), cx);
         assert_error("#'", Error::MissingQuotedItem(0), cx);
         assert_error("#a", Error::UnknownMacroCharacter('a', 0), cx);
+        assert_error("(a #')", Error::MissingQuotedItem(3), cx);
+        check_reader!(
+            list!(quote, list!(intern("lambda", cx), false, 1; cx); cx),
+            "#'(lambda () 1)",
+            cx
+        );
+        check_reader!(list!(quote, list!(sym::QUOTE, sym::IF; cx); cx), "#''if", cx);
+        check_reader!(list!(sym::QUOTE, list!(quote, sym::IF; cx); cx), "'#'if", cx);
     }
 
     #[test]
@@ -676,6 +841,48 @@ baz""#,
         check_reader!(vec, "[1 2 3]", cx);
     }
 
+    #[test]
+    fn test_read_byte_code() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        let obj = read("#[257 [192 135] [foo \"bar\"] 2]", cx).unwrap().0;
+        let ObjectType::ByteFn(func) = obj.untag() else { panic!("expected byte-code: {obj}") };
+        assert_eq!(func.codes(), [192, 135]);
+        assert_eq!(func.args, FnArgs::from_arg_spec(257).unwrap());
+        // printing and reading a byte-code function should round trip
+        let printed = obj.to_string();
+        let reread = read(&printed, cx).unwrap().0;
+        assert_eq!(reread.to_string(), printed);
+        let obj = read("#[0 \"\u{c0}\u{87}\" [] 1]", cx).unwrap().0;
+        let ObjectType::ByteFn(func) = obj.untag() else { panic!("expected byte-code: {obj}") };
+        assert_eq!(func.codes(), [192, 135]);
+        assert_error("#[0 [192]]", Error::InvalidByteCode(0), cx);
+        assert_error("#[0 [256] [] 1]", Error::InvalidByteCode(0), cx);
+        // jump past the end of the code
+        assert_error("#[0 [130 10 0 135] [] 1]", Error::InvalidByteCode(0), cx);
+        assert_error("#[(x) [192] [] 1]", Error::InvalidByteCode(0), cx);
+    }
+
+    #[test]
+    fn test_stream_reader() {
+        let roots = &RootSet::default();
+        let cx = &Context::new(roots);
+        let input = " 1 (foo \"bar\") ; comment\n [a b] 'c ";
+        let mut stream = StreamReader::new(input);
+        let mut pos = 0;
+        // reading from the stream should match reading resliced input
+        while let Ok((expect, len)) = read(&input[pos..], cx) {
+            assert_eq!(stream.read(cx).unwrap(), expect);
+            pos += len;
+            assert_eq!(stream.pos(), pos);
+        }
+        assert_eq!(stream.read(cx), Err(Error::EmptyStream));
+
+        let mut stream = StreamReader::new("1 (2");
+        assert_eq!(stream.read(cx).unwrap(), 1);
+        assert_eq!(stream.read(cx), Err(Error::MissingCloseParen(2)));
+    }
+
     fn assert_error(input: &str, error: Error, cx: &Context) {
         let result = read(input, cx).err().unwrap();
         assert_eq!(result, error);
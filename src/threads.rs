@@ -24,7 +24,7 @@ fn go_internal(obj: Object) -> JoinHandle<()> {
         root!(env, new(Env), cx);
         let obj = unsafe { Object::from_raw(raw) };
         root!(obj, cx);
-        _ = crate::interpreter::eval(obj, None, env, cx);
+        _ = crate::interpreter::eval_toplevel(obj, true, env, cx);
     })
 }
 